@@ -19,6 +19,15 @@ class HTTPException(IssueAPIError):
 class LibraryException(IssueAPIError):
     ...
 
+class ValidationException(IssueAPIError):
+    ...
+
+class NotFoundException(HTTPException):
+    ...
+
+class ChecksumMismatchException(IssueAPIError):
+    ...
+
 
 class IssueRepository:
     def __init__(self,
@@ -26,6 +35,7 @@ class IssueRepository:
                  credentials: tuple[str, str] | None = None,
                  label_caching_policy: str = 'no_caching',
                  config_handling_policy: str = 'read_fetch_write_fetch',
+                 prefetch_policy: str = 'no_prefetching',
                  allow_self_signed_certificates: bool = False):
         ...
 
@@ -35,18 +45,69 @@ class IssueRepository:
                    token: str,
                    label_caching_policy: str = 'no_caching',
                    config_handling_policy: str = 'read_fetch_write_fetch',
+                   prefetch_policy: str = 'no_prefetching',
                    allow_self_signed_certificates: bool = False):
         ...
 
     def __repr__(self) -> str:
         ...
 
+    def issue_from_dict(self, data: dict[str, typing.Any]) -> Issue:
+        ...
+
     def search(self, /,
                q: Query, *,
                attributes: list[str] = (),
                load_labels: bool = False) -> list[Issue]:
         ...
 
+    def labelling_comments(self, query: Query) -> dict[str, list[Comment]]:
+        ...
+
+    def search_labelling_comments(self,
+                                  query: Query, *,
+                                  author: str | None = None,
+                                  contains: str | None = None,
+                                  pattern: str | None = None,
+                                  case_sensitive: bool = False,
+                                  after: str | None = None,
+                                  before: str | None = None,
+                                  attributes: list[str] = [],
+                                  load_labels: bool = False) -> list[tuple[Issue, Comment]]:
+        ...
+
+    def export_labelling_comments(self, query: Query, path: str):
+        ...
+
+    def issues_in_review(self) -> list[Review]:
+        ...
+
+    def bulk_start_review(self, issues: list[Issue], reason: str | None = None):
+        ...
+
+    def bulk_finish_review(self,
+                           reviews: list[tuple[Issue, Label | dict[str, typing.Any] | None]], *,
+                           schema: str = 'architectural'):
+        ...
+
+    def label_agreement(self,
+                        query: Query, *,
+                        schema: str = 'architectural') -> list[dict[str, typing.Any]]:
+        ...
+
+    def start_labelling_session(self,
+                                query: Query | None = None, *,
+                                batch_size: int = 50,
+                                attributes: list[str] = [],
+                                load_labels: bool = False) -> LabellingSession:
+        ...
+
+    def prefetch(self, issues: list[Issue], attributes: list[str]):
+        ...
+
+    def set_issue_data_batching(self, batch_size: int, *, workers: int = 4):
+        ...
+
     @property
     def projects(self) -> list[Project]:
         ...
@@ -65,7 +126,44 @@ class IssueRepository:
     def tags(self) -> list[Tag]:
         ...
 
-    def add_new_tag(self, name: str, description: str):
+    def add_new_tag(self, name: str, description: str, *, tag_type: str | None = None):
+        ...
+
+    def tags_by_type(self, tag_type: str) -> list[Tag]:
+        ...
+
+    @property
+    def authors(self) -> list[str]:
+        ...
+
+    def predictions_with_labels(self,
+                                version: Version,
+                                query: Query, *,
+                                attributes: list[str] = []) -> list[tuple[Issue, Prediction, Label | None]]:
+        ...
+
+    def issues_by_author(self,
+                         author: str, *,
+                         attributes: list[str] = [],
+                         load_labels: bool = False) -> list[Issue]:
+        ...
+
+    def get_tag(self, name: str) -> Tag:
+        ...
+
+    def bulk_add_tags(self,
+                      tags: dict[Issue | str, list[str]], *,
+                      chunk_size: int = 1000) -> dict[str, typing.Any]:
+        ...
+
+    def bulk_remove_tags(self,
+                         tags: dict[Issue | str, list[str]], *,
+                         chunk_size: int = 1000) -> dict[str, typing.Any]:
+        ...
+
+    def bulk_apply_tag_diff(self,
+                            diffs: dict[Issue | str, tuple[list[str], list[str]]], *,
+                            chunk_size: int = 1000) -> dict[str, typing.Any]:
         ...
 
     @property
@@ -91,6 +189,18 @@ class IssueRepository:
     def get_model_by_id(self, id: str) -> Model:
         ...
 
+    def get_model_version(self, model_id: str, version_id: str) -> Version:
+        ...
+
+    def get_test_run(self, model_id: str, run_id: str) -> TestRun:
+        ...
+
+    def versions_using_embedding(self, embedding_id: str, *, strict: bool = True) -> list[Version]:
+        ...
+
+    def versions_using_file(self, file_id: str, *, strict: bool = True) -> list[Version]:
+        ...
+
     def add_model(self, name: str, config: dict[str, typing.Any]) -> Model:
         ...
 
@@ -109,6 +219,34 @@ class IssueRepository:
     def delete_file(self, file: File):
         ...
 
+    def set_model_config_schema(self, schema: dict[str, typing.Any] | None):
+        ...
+
+    @property
+    def model_config_schema(self) -> dict[str, typing.Any] | None:
+        ...
+
+    def register_label_schema(self, schema: LabelSchema):
+        ...
+
+    def label_schemas(self) -> list[LabelSchema]:
+        ...
+
+    def get_label_schema(self, name: str) -> LabelSchema:
+        ...
+
+    def stats(self) -> dict[str, int | float]:
+        ...
+
+    def reset_stats(self):
+        ...
+
+    def set_rate_limit(self, requests_per_second: float | None, *, burst: int = 1):
+        ...
+
+    def set_max_in_flight(self, limit: int | None):
+        ...
+
 
 class Project:
 
@@ -181,6 +319,9 @@ class Query:
     def not_tag(self, name: str) -> Query:
         ...
     
+    def author(self, name: str) -> Query:
+        ...
+
     def to_json(self) -> object:
         ...
 
@@ -220,8 +361,167 @@ class Label:
         ...
 
 
+class Prediction:
+
+    def __init__(self,
+                 existence: bool,
+                 executive: bool,
+                 property: bool, *,
+                 existence_probability: float | None = None,
+                 executive_probability: float | None = None,
+                 property_probability: float | None = None,
+                 confidence: float | None = None):
+        ...
+
+    @classmethod
+    def from_dict(cls, data: dict[str, typing.Any]) -> Prediction:
+        ...
+
+    def to_dict(self) -> dict[str, typing.Any]:
+        ...
+
+    def __repr__(self) -> str:
+        ...
+
+    def __eq__(self, other) -> bool | NotImplemented:
+        ...
+
+    @property
+    def existence(self) -> bool:
+        ...
+
+    @property
+    def executive(self) -> bool:
+        ...
+
+    @property
+    def property(self) -> bool:
+        ...
+
+    @property
+    def existence_probability(self) -> float | None:
+        ...
+
+    @property
+    def executive_probability(self) -> float | None:
+        ...
+
+    @property
+    def property_probability(self) -> float | None:
+        ...
+
+    @property
+    def confidence(self) -> float | None:
+        ...
+
+    @property
+    def label(self) -> Label:
+        ...
+
+    @property
+    def non_architectural(self) -> bool:
+        ...
+
+
+class LabelSchema:
+    def __init__(self, name: str, fields: list[dict[str, typing.Any]]):
+        ...
+
+    def __repr__(self) -> str:
+        ...
+
+    @property
+    def name(self) -> str:
+        ...
+
+    @property
+    def fields(self) -> list[dict[str, typing.Any]]:
+        ...
+
+    def validate(self, label: dict[str, typing.Any]):
+        ...
+
+
+class Review:
+    def __repr__(self) -> str:
+        ...
+
+    @property
+    def issue_id(self) -> str:
+        ...
+
+    @property
+    def reason(self) -> str | None:
+        ...
+
+    @property
+    def requested_by(self) -> str | None:
+        ...
+
+    @property
+    def reviewers(self) -> list[str]:
+        ...
+
+    @property
+    def comments(self) -> list[Comment]:
+        ...
+
+    def issue(self, attributes: list[str] = [], load_labels: bool = False) -> Issue:
+        ...
+
+    def finish(self,
+               label: Label | dict[str, typing.Any] | None = None, *,
+               schema: str = 'architectural'):
+        ...
+
+
+class LabellingSession:
+    @property
+    def issues(self) -> list[Issue]:
+        ...
+
+    def progress(self) -> dict[str, int]:
+        ...
+
+    def set_label(self,
+                  issue: Issue,
+                  label: Label | dict[str, typing.Any], *,
+                  schema: str = 'architectural'):
+        ...
+
+    def add_comment(self, issue: Issue, text: str):
+        ...
+
+    def mark_for_review(self, issue: Issue, in_review: bool = True):
+        ...
+
+    def discard(self, issue: Issue):
+        ...
+
+    def commit(self):
+        ...
+
+
 class Issue:
-    def __new__(cls):
+    def __new__(cls, state: dict[str, typing.Any] | None = None):
+        ...
+
+    @classmethod
+    def from_dict(cls,
+                  data: dict[str, typing.Any],
+                  repository: IssueRepository | None = None) -> Issue:
+        ...
+
+    def to_dict(self) -> dict[str, typing.Any]:
+        ...
+
+    def __getstate__(self) -> dict[str, typing.Any]:
+        ...
+
+    def __setstate__(self, state: dict[str, typing.Any]):
+        ...
+
+    def __reduce__(self) -> tuple[type[Issue], tuple[dict[str, typing.Any]]]:
         ...
 
     def __repr__(self) -> str:
@@ -245,6 +545,19 @@ class Issue:
     def manual_label(self, value: Label):
         ...
 
+    def get_label(self, schema: str = 'architectural') -> dict[str, typing.Any] | None:
+        ...
+
+    def set_label(self, label: dict[str, typing.Any], *, schema: str = 'architectural'):
+        ...
+
+    def get_annotator_labels(self, schema: str = 'architectural') -> dict[str, dict[str, typing.Any]]:
+        ...
+
+    @property
+    def label_history(self) -> list[dict[str, typing.Any]]:
+        ...
+
     def invalidate_label_cache(self):
         ...
 
@@ -258,6 +571,14 @@ class Issue:
     def remove_tag(self, name: str):
         ...
 
+    def start_review(self, reason: str | None = None):
+        ...
+
+    def finish_review(self,
+                      label: Label | dict[str, typing.Any] | None = None, *,
+                      schema: str = 'architectural'):
+        ...
+
     @property
     def is_in_review(self) -> bool:
         ...
@@ -270,6 +591,10 @@ class Issue:
     def labelling_comments(self) -> list[Comment]:
         ...
 
+    @property
+    def labelling_comment_threads(self) -> list[CommentThread]:
+        ...
+
     def add_labelling_comment(self, text: str):
         ...
 
@@ -373,6 +698,56 @@ class Comment:
     def body(self, text: str):
         ...
 
+    @property
+    def identifier(self) -> str:
+        ...
+
+    @property
+    def issue_id(self) -> str:
+        ...
+
+    @property
+    def timestamp(self) -> str | None:
+        ...
+
+    @property
+    def edit_history(self) -> list[dict[str, str | None]]:
+        ...
+
+    @property
+    def reply_to(self) -> str | None:
+        ...
+
+    @property
+    def resolved(self) -> bool:
+        ...
+
+    @resolved.setter
+    def resolved(self, resolved: bool):
+        ...
+
+    def reply(self, text: str) -> Comment:
+        ...
+
+    def to_dict(self) -> dict[str, typing.Any]:
+        ...
+
+
+class CommentThread:
+    def __repr__(self) -> str:
+        ...
+
+    @property
+    def comment(self) -> Comment:
+        ...
+
+    @property
+    def replies(self) -> list[CommentThread]:
+        ...
+
+    def to_dict(self) -> dict[str, typing.Any]:
+        ...
+
 
 class Tag:
     def __repr__(self) -> str:
@@ -400,6 +775,22 @@ class Tag:
     def tag_type(self) -> str:
         ...
 
+    def delete(self):
+        ...
+
+    def issues(self, attributes: list[str] = [], load_labels: bool = False) -> list[Issue]:
+        ...
+
+    @property
+    def usage_count(self) -> int:
+        ...
+
+    def rename(self, new_name: str) -> dict[str, typing.Any]:
+        ...
+
+    def merge_into(self, target: Tag) -> dict[str, typing.Any]:
+        ...
+
 
 class Embedding:
     def __repr__(self) -> str:
@@ -469,6 +860,12 @@ class Model:
     def config(self, config: dict[str, typing.Any]):
         ...
 
+    def config_hash(self) -> str:
+        ...
+
+    def diff_config(self, other: Model) -> list[dict[str, typing.Any]]:
+        ...
+
     @property
     def versions(self) -> list[Version]:
         ...
@@ -476,7 +873,11 @@ class Model:
     def get_version_by_id(self, id: str) -> Version:
         ...
 
-    def add_version(self, path: str, description: str | None = None) -> Version:
+    def add_version(self,
+                    path: str,
+                    description: str | None = None,
+                    *,
+                    progress: typing.Callable[[int, int | None], None] | None = None) -> Version:
         ...
 
     def remove_version(self, version: Version):
@@ -489,6 +890,11 @@ class Model:
     def get_run_by_id(self, id: str) -> TestRun:
         ...
 
+    def add_test_run_records(self,
+                             records: list[dict[str, typing.Any]],
+                             description: str | None = None) -> TestRun:
+        ...
+
     def add_test_run(self,
                      data: list[typing.Any],
                      description: str | None = None) -> TestRun:
@@ -514,7 +920,14 @@ class Version:
     def version_id(self) -> str:
         ...
 
-    def download(self, path: str):
+    def download(self,
+                 path: str,
+                 *,
+                 progress: typing.Callable[[int, int | None], None] | None = None):
+        ...
+
+    @property
+    def checksum(self) -> str | None:
         ...
 
     @property
@@ -525,9 +938,37 @@ class Version:
     def predictions(self, predictions: dict[str, typing.Any]):
         ...
 
+    def get_typed_predictions(self, issues: list[str] | None = None) -> dict[str, Prediction]:
+        ...
+
+    def get_predictions_for_query(self, query: Query) -> dict[str, Prediction]:
+        ...
+
+    def iter_predictions(self,
+                         issues: list[str] | None = None, *,
+                         typed: bool = False) -> PredictionIterator:
+        ...
+
+    def store_typed_predictions(self, predictions: dict[str, Prediction]):
+        ...
+
     def delete_predictions(self):
         ...
 
+    @staticmethod
+    def compare(versions: list[Version],
+                query: Query, *,
+                threshold: float = 0.5) -> RunComparison:
+        ...
+
+    def evaluate(self, query: Query, *, threshold: float = 0.5) -> dict[str, typing.Any]:
+        ...
+
+    def store_evaluation(self,
+                         report: dict[str, typing.Any],
+                         description: str | None = None) -> TestRun:
+        ...
+
     @property
     def description(self) -> str:
         ...
@@ -536,6 +977,23 @@ class Version:
     def description(self, description: str):
         ...
 
+    @property
+    def provenance(self) -> dict[str, typing.Any] | None:
+        ...
+
+    @provenance.setter
+    def provenance(self, provenance: dict[str, typing.Any] | None):
+        ...
+
+
+class PredictionIterator:
+
+    def __iter__(self) -> PredictionIterator:
+        ...
+
+    def __next__(self) -> tuple[str, Prediction | typing.Any]:
+        ...
+
 
 class TestRun:
 
@@ -554,6 +1012,17 @@ class TestRun:
     def data(self) -> list[typing.Any]:
         ...
 
+    @property
+    def records(self) -> list[dict[str, typing.Any]]:
+        ...
+
+    def aggregate(self, split: str | None = None) -> dict[str, dict[str, typing.Any]]:
+        ...
+
+    @staticmethod
+    def compare(runs: list[TestRun], *, split: str | None = None) -> RunComparison:
+        ...
+
     @property
     def description(self) -> str:
         ...
@@ -563,6 +1032,28 @@ class TestRun:
         ...
 
 
+class RunComparison:
+
+    def __str__(self) -> str:
+        ...
+
+    @property
+    def runs(self) -> list[str]:
+        ...
+
+    def to_dict(self) -> dict[str, typing.Any]:
+        ...
+
+    def to_table(self) -> str:
+        ...
+
+    def to_csv(self) -> str:
+        ...
+
+    def to_markdown(self) -> str:
+        ...
+
+
 class File:
 
     def __repr__(self) -> str:
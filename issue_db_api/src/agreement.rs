@@ -0,0 +1,243 @@
+use std::collections::{BTreeSet, HashMap};
+use serde_json::{Map, Value};
+use crate::errors::APIResult;
+use crate::label_schemas::LabelSchema;
+
+/// A label as set by a single annotator at a given point in time.
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct LabelRecord {
+    pub author: String,
+    pub timestamp: String,
+    pub label: Map<String, Value>
+}
+
+/// Agreement between annotators for a single field of a label schema.
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgreementReport {
+    pub dimension: String,
+    /// Number of issues labelled by at least two annotators.
+    pub issues: usize,
+    pub annotators: usize,
+    /// Cohen's kappa for every pair of annotators with overlapping issues.
+    pub pairwise_cohens_kappa: Vec<(String, String, f64)>,
+    pub fleiss_kappa: Option<f64>
+}
+
+/// Per-annotator labels for a set of issues: issue -> annotator -> label.
+pub(crate) type AnnotatorLabels = HashMap<String, HashMap<String, Map<String, Value>>>;
+
+/// Compute agreement statistics for every field in `schema`.
+pub(crate) fn compute_agreement(schema: &LabelSchema,
+                                labels: &AnnotatorLabels) -> APIResult<Vec<AgreementReport>> {
+    // issue -> annotator -> label (for this schema only)
+    let mut extracted: HashMap<&String, HashMap<&String, Map<String, Value>>> = HashMap::new();
+    for (issue, by_author) in labels {
+        for (author, payload) in by_author {
+            if let Some(label) = schema.extract(payload)? {
+                extracted.entry(issue).or_default().insert(author, label);
+            }
+        }
+    }
+    let annotators = extracted.values()
+        .flat_map(|m| m.keys().copied())
+        .collect::<BTreeSet<_>>();
+    let reports = schema.fields()
+        .iter()
+        .map(|field| {
+            // issue -> annotator -> category
+            let ratings = extracted.values()
+                .map(|by_author| {
+                    by_author.iter()
+                        .filter_map(|(a, l)| match l.get(&field.name) {
+                            // Unset optional fields are not a rating
+                            None | Some(Value::Null) => None,
+                            Some(v) => Some((*a, v.to_string()))
+                        })
+                        .collect::<HashMap<_, _>>()
+                })
+                .filter(|r| r.len() >= 2)
+                .collect::<Vec<_>>();
+            let mut pairwise = Vec::new();
+            for (i, a) in annotators.iter().enumerate() {
+                for b in annotators.iter().skip(i + 1) {
+                    let pairs = ratings.iter()
+                        .filter_map(|r| Some((r.get(a)?.as_str(), r.get(b)?.as_str())))
+                        .collect::<Vec<_>>();
+                    if let Some(kappa) = cohens_kappa(&pairs) {
+                        pairwise.push(((*a).clone(), (*b).clone(), kappa));
+                    }
+                }
+            }
+            let per_issue = ratings.iter()
+                .map(|r| r.values().map(String::as_str).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            AgreementReport{
+                dimension: field.name.clone(),
+                issues: ratings.len(),
+                annotators: annotators.len(),
+                pairwise_cohens_kappa: pairwise,
+                fleiss_kappa: fleiss_kappa(&per_issue)
+            }
+        })
+        .collect();
+    Ok(reports)
+}
+
+/// Cohen's kappa for two annotators. Returns `None` if there are no
+/// ratings, or if agreement by chance is certain (kappa is undefined).
+pub fn cohens_kappa(pairs: &[(&str, &str)]) -> Option<f64> {
+    if pairs.is_empty() {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let observed = pairs.iter().filter(|(a, b)| a == b).count() as f64 / n;
+    let mut counts_a: HashMap<&str, f64> = HashMap::new();
+    let mut counts_b: HashMap<&str, f64> = HashMap::new();
+    for (a, b) in pairs {
+        *counts_a.entry(a).or_default() += 1.0;
+        *counts_b.entry(b).or_default() += 1.0;
+    }
+    let expected = counts_a.iter()
+        .map(|(k, ca)| ca * counts_b.get(k).copied().unwrap_or(0.0))
+        .sum::<f64>() / (n * n);
+    if expected >= 1.0 {
+        None
+    } else {
+        Some((observed - expected) / (1.0 - expected))
+    }
+}
+
+/// Fleiss' kappa over a set of items, each rated by at least two annotators.
+/// The number of ratings may differ per item; the per-item agreement is
+/// then normalised by that item's number of ratings.
+pub fn fleiss_kappa(ratings: &[Vec<&str>]) -> Option<f64> {
+    let items = ratings.iter().filter(|r| r.len() >= 2).collect::<Vec<_>>();
+    if items.is_empty() {
+        return None;
+    }
+    let mut totals: HashMap<&str, f64> = HashMap::new();
+    let mut total_ratings = 0.0;
+    let mut mean_agreement = 0.0;
+    for item in items.iter() {
+        let mut counts: HashMap<&str, f64> = HashMap::new();
+        for category in item.iter() {
+            *counts.entry(category).or_default() += 1.0;
+            *totals.entry(category).or_default() += 1.0;
+        }
+        let n = item.len() as f64;
+        total_ratings += n;
+        let squares = counts.values().map(|c| c * c).sum::<f64>();
+        mean_agreement += (squares - n) / (n * (n - 1.0));
+    }
+    mean_agreement /= items.len() as f64;
+    let expected = totals.values()
+        .map(|c| (c / total_ratings).powi(2))
+        .sum::<f64>();
+    if expected >= 1.0 {
+        None
+    } else {
+        Some((mean_agreement - expected) / (1.0 - expected))
+    }
+}
+
+
+#[cfg(test)]
+// `std::iter::repeat_n` would need Rust 1.82
+#[allow(clippy::manual_repeat_n)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::label_schemas::{LabelField, LabelFieldType};
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-3, "expected {expected}, got {actual}");
+    }
+
+    #[test]
+    fn cohens_kappa_two_by_two() {
+        let mut pairs = Vec::new();
+        pairs.extend(std::iter::repeat(("yes", "yes")).take(20));
+        pairs.extend(std::iter::repeat(("yes", "no")).take(5));
+        pairs.extend(std::iter::repeat(("no", "yes")).take(10));
+        pairs.extend(std::iter::repeat(("no", "no")).take(15));
+        assert_close(cohens_kappa(&pairs).unwrap(), 0.4);
+    }
+
+    #[test]
+    fn cohens_kappa_undefined() {
+        assert_eq!(cohens_kappa(&[]), None);
+        assert_eq!(cohens_kappa(&[("yes", "yes"), ("yes", "yes")]), None);
+    }
+
+    #[test]
+    fn fleiss_kappa_original_example() {
+        let counts: [[usize; 5]; 10] = [
+            [0, 0, 0, 0, 14],
+            [0, 2, 6, 4, 2],
+            [0, 0, 3, 5, 6],
+            [0, 3, 9, 2, 0],
+            [2, 2, 8, 1, 1],
+            [7, 7, 0, 0, 0],
+            [3, 2, 6, 3, 0],
+            [2, 5, 3, 2, 2],
+            [6, 5, 2, 1, 0],
+            [0, 2, 2, 3, 7]
+        ];
+        let categories = ["1", "2", "3", "4", "5"];
+        let ratings = counts.iter()
+            .map(|row| {
+                row.iter()
+                    .zip(categories)
+                    .flat_map(|(n, c)| std::iter::repeat(c).take(*n))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_close(fleiss_kappa(&ratings).unwrap(), 0.210);
+    }
+
+    #[test]
+    fn fleiss_kappa_undefined() {
+        assert_eq!(fleiss_kappa(&[]), None);
+        assert_eq!(fleiss_kappa(&[vec!["a"], vec!["b"]]), None);
+        assert_eq!(fleiss_kappa(&[vec!["a", "a"], vec!["a", "a"]]), None);
+    }
+
+    #[test]
+    fn compute_agreement_ignores_unset_fields() {
+        let schema = LabelSchema::new(
+            "risk".to_string(),
+            vec![LabelField{
+                name: "level".to_string(),
+                field_type: LabelFieldType::Category{
+                    options: vec!["low".to_string(), "high".to_string()]
+                },
+                required: false
+            }]
+        ).unwrap();
+        let label = |level: Value| {
+            let mut payload = Map::new();
+            payload.insert("risk".to_string(), json!({"level": level}));
+            payload
+        };
+        let mut labels: AnnotatorLabels = HashMap::new();
+        for (issue, a, b, c) in [
+            ("1", json!("low"), json!("low"), Value::Null),
+            ("2", json!("high"), json!("high"), Value::Null),
+            ("3", json!("low"), Value::Null, Value::Null)
+        ] {
+            let by_author = labels.entry(issue.to_string()).or_default();
+            by_author.insert("a".to_string(), label(a));
+            by_author.insert("b".to_string(), label(b));
+            by_author.insert("c".to_string(), label(c));
+        }
+        let reports = compute_agreement(&schema, &labels).unwrap();
+        assert_eq!(reports.len(), 1);
+        let report = &reports[0];
+        assert_eq!(report.issues, 2);
+        assert_eq!(report.pairwise_cohens_kappa, vec![("a".to_string(), "b".to_string(), 1.0)]);
+        assert_close(report.fleiss_kappa.unwrap(), 1.0);
+    }
+}
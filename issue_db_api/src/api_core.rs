@@ -1,29 +1,65 @@
 use std::collections::HashMap;
 use std::io::Cursor;
-use std::time::Duration;
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "blocking")]
 use reqwest::blocking::multipart;
 
 use serde_json::{Map, Value};
-use lazy_init::Lazy;
 
-use crate::comments::UnboundComment;
+use crate::comments::{convert_raw_comments, RawComment, UnboundComment};
 use crate::config::IssueAttribute;
 use crate::embedding::UnboundEmbedding;
-use crate::schemas::raw_issue_response::RawIssueData;
+use crate::schemas::raw_issue_response::{deserialize_some, RawIssueData};
 use crate::query::Query;
 use crate::labels::Label;
-use crate::tags::UnboundTag;
-use crate::util::initialize_lazy_field;
+use crate::label_schemas::LabelSchemaRegistry;
+use crate::model_configs::ConfigSchemaSlot;
+use crate::agreement::{AnnotatorLabels, LabelRecord};
+use crate::review::UnboundReview;
+use crate::tags::{TagType, UnboundTag};
+use crate::util::CacheLockError;
 use crate::errors::APIResult;
 use crate::errors::*;
 use crate::files::UnboundFile;
+use crate::metrics::MetricsCollector;
+use crate::prefetch::LoadingGroup;
+use crate::throttle::RequestThrottle;
 use crate::models::{ModelInfo, UnboundModelConfig, UnboundModelVersion, UnboundTestRun};
+use crate::prediction_stream::{PredictionStream, PredictionUpload};
 use crate::projects::{Project, UnboundProject};
+use crate::transfers::{content_range, partial_download_path, sha256_file, ProgressCallback, ProgressReader};
 
 const CONNECT_TIMEOUT: Duration = Duration::new(30, 0);
 const READ_WRITE_TIMEOUT: Duration = Duration::new(10 * 60, 0);
+const DEFAULT_ISSUE_DATA_BATCH_SIZE: usize = 1000;
+const DEFAULT_ISSUE_DATA_WORKERS: usize = 4;
+const MAX_DOWNLOAD_ATTEMPTS: u32 = 5;
+const DOWNLOAD_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Result of a download into a sink which may already hold
+/// the first part of the resource.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum DownloadOutcome {
+    Complete,
+    /// The server reported a size or range which does not fit the data
+    /// in the sink, so the sink does not hold a prefix of the resource.
+    Mismatch
+}
+
+impl DownloadOutcome {
+    fn into_result(self, resource: &str) -> APIResult<()> {
+        match self {
+            DownloadOutcome::Complete => Ok(()),
+            DownloadOutcome::Mismatch => {
+                let msg = format!("Download of {resource} changed size or range while being received");
+                Err(APIError::GenericError(msg))
+            }
+        }
+    }
+}
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
 //////////////////////////////////////////////////////////////////////////////////////////////////
@@ -40,32 +76,53 @@ pub struct IssueAPI {
     token: Option<String>,
     allow_unsafe_ssl: bool,
     client: reqwest::blocking::Client,
+    metrics: Arc<MetricsCollector>,
+    throttle: Arc<RequestThrottle>,
+    issue_data_batching: Arc<IssueDataBatching>,
+    label_schemas: Arc<LabelSchemaRegistry>,
+    model_config_schema: Arc<ConfigSchemaSlot>
 }
 
+/// Issue attributes as returned by the `issue-data` endpoint.
+/// Attributes which have not been loaded (yet) are `None`.
 #[allow(unused)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct IssueFields {
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub comments: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub priority: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "deserialize_some")]
+    pub resolution: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub issue_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub issue_links: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "deserialize_some")]
+    pub parent: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub subtasks: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub watches: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub votes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub date_created: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub date_updated: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "deserialize_some")]
+    pub date_resolved: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub labels: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub components: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub affected_versions: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub fix_versions: Option<Vec<String>>
+}
+
+/// The (partially) loaded data of a single issue.
+/// All fields live behind a single lock, so that data loaded
+/// later on can be merged in through a shared reference.
+#[allow(unused)]
+#[derive(Debug)]
 pub struct IssueData {
     ident: String,
-    key: Lazy<String>,
-    summary: Lazy<String>,
-    description: Lazy<String>,
-    comments: Lazy<Vec<String>>,
-    status: Lazy<String>,
-    priority: Lazy<String>,
-    resolution: Lazy<Option<String>>,
-    issue_type: Lazy<String>,
-    issue_links: Lazy<Vec<String>>,
-    parent: Lazy<Option<String>>,
-    subtasks: Lazy<Vec<String>>,
-    watches: Lazy<u64>,
-    votes: Lazy<u64>,
-    date_created: Lazy<String>,
-    date_updated: Lazy<String>,
-    date_resolved: Lazy<Option<String>>,
-    labels: Lazy<Vec<String>>,
-    components: Lazy<Vec<String>>,
-    affected_versions: Lazy<Vec<String>>,
-    fix_versions: Lazy<Vec<String>>
+    fields: RwLock<IssueFields>,
+    group: RwLock<Option<Arc<LoadingGroup>>>
 }
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
@@ -73,7 +130,15 @@ pub struct IssueData {
 // Auxiliary structs and enums
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// Settings controlling how `issue-data` requests are split into
+/// batches, and how many batches are requested concurrently.
 #[derive(Debug)]
+struct IssueDataBatching {
+    batch_size: AtomicUsize,
+    workers: AtomicUsize
+}
+
+#[derive(Debug, Copy, Clone)]
 #[allow(unused)]
 enum Verb {
     Get,
@@ -83,179 +148,287 @@ enum Verb {
     Delete
 }
 
-macro_rules! maybe_copy_attribute {
-    ($self:ident, $other:ident, $attr:ident) => {
-        {
-            if let Some(x) = $other.$attr.get() {
-                $self.$attr.get_or_create(|| x.clone());
+impl std::fmt::Display for Verb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Verb::Get => "GET",
+            Verb::Post => "POST",
+            Verb::Patch => "PATCH",
+            Verb::Put => "PUT",
+            Verb::Delete => "DELETE"
+        };
+        write!(f, "{}", text)
+    }
+}
+
+macro_rules! merge_fields {
+    ($self:ident, $other:ident, $( $attr:ident ),*) => {
+        $(
+            if $self.$attr.is_none() {
+                $self.$attr = $other.$attr;
             }
+        )*
+    }
+}
+
+macro_rules! attribute_is_loaded {
+    ($self:ident, $attr:expr, $( $variant:ident => $field:ident ),*) => {
+        match $attr {
+            $( IssueAttribute::$variant => $self.$field.is_some() ),*
         }
     }
 }
 
-macro_rules! load_lazy_attribute {
+macro_rules! load_attribute {
     ($self:ident, $attr:ident, $e:expr, $api:expr) => {
         {
-            if let Some(x) = $self.$attr.get() {
-                Ok(x)
-            } else {
-                let value = $api.get_issue_data(vec![$self.ident.clone()], vec![$e])?
-                    .get(&$self.ident)
-                    .expect("Issue data lookup failed")
-                    .$attr
-                    .get()
-                    .expect("Invalid missing data during lookup")
-                    .clone();
-                let x = $self.$attr.get_or_create(|| value);
-                Ok(x)
+            if let Some(x) = $self.read()?.$attr.clone() {
+                return Ok(x);
             }
+            if let Some(group) = $self.group()? {
+                group.load_attribute($api, $e)?;
+                if let Some(x) = $self.read()?.$attr.clone() {
+                    return Ok(x);
+                }
+            }
+            let fetched = $api.get_issue_data(vec![$self.ident.clone()], vec![$e])?
+                .remove(&$self.ident)
+                .expect("Issue data lookup failed");
+            $self.update(fetched)?;
+            let value = $self.read()?
+                .$attr
+                .clone()
+                .expect("Invalid missing data during lookup");
+            Ok(value)
+        }
+    }
+}
+
+#[allow(unused)]
+impl IssueFields {
+    fn from_raw_data(value: RawIssueData) -> Self {
+        Self {
+            key: value.key,
+            summary: value.summary,
+            description: value.description,
+            comments: value.comments.map(|v| v.into_iter().map(|c| c.body).collect()),
+            status: value.status.map(|x| x.name),
+            resolution: value.resolution.map(|x| x.map(|y| y.name)),
+            priority: value.priority.map(|x| x.name),
+            issue_type: value.issuetype.map(|x| x.name),
+            issue_links: Some(vec![]),
+            parent: value.parent,
+            subtasks: value.subtasks,
+            watches: value.watches.map(|x| x.watch_count),
+            votes: value.votes.map(|x| x.votes),
+            date_created: value.created,
+            date_updated: value.updated,
+            date_resolved: value.resolutiondate,
+            labels: value.labels,
+            components: value.components.map(|v| v.into_iter().map(|c| c.name).collect()),
+            affected_versions: value.versions.map(|v| v.into_iter().map(|c| c.name).collect()),
+            fix_versions: value.fix_versions.map(|v| v.into_iter().map(|c| c.name).collect())
         }
     }
+
+    /// Fill in all attributes which are missing in `self`
+    /// using the values from `other`.
+    pub fn merge(&mut self, other: IssueFields) {
+        merge_fields!(
+            self, other,
+            key, summary, description, comments, parent, subtasks,
+            issue_links, status, priority, resolution, issue_type,
+            watches, votes, date_created, date_updated, date_resolved,
+            labels, components, affected_versions, fix_versions
+        );
+    }
+
+    pub fn is_loaded(&self, attribute: IssueAttribute) -> bool {
+        attribute_is_loaded!(
+            self, attribute,
+            Key => key, Summary => summary, Description => description,
+            Comments => comments, Parent => parent, Subtasks => subtasks,
+            IssueLinks => issue_links, Status => status, Priority => priority,
+            Resolution => resolution, IssueType => issue_type,
+            Watches => watches, Votes => votes,
+            DateCreated => date_created, DateUpdated => date_updated,
+            DateResolved => date_resolved, Labels => labels,
+            Components => components, AffectedVersions => affected_versions,
+            FixVersions => fix_versions
+        )
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct IssueDataSnapshot {
+    id: String,
+    #[serde(flatten)] fields: IssueFields
+}
+
+impl serde::Serialize for IssueData {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let fields = self.fields
+            .read()
+            .map_err(|_| serde::ser::Error::custom(CacheLockError{}))?
+            .clone();
+        IssueDataSnapshot{id: self.ident.clone(), fields}.serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for IssueData {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let snapshot = IssueDataSnapshot::deserialize(deserializer)?;
+        Ok(Self::from_fields(snapshot.id, snapshot.fields))
+    }
+}
+
+impl Clone for IssueData {
+    /// Copy the currently loaded data. The copy
+    /// is not a member of any loading group.
+    fn clone(&self) -> Self {
+        let fields = self.fields
+            .read()
+            .map(|f| f.clone())
+            .unwrap_or_default();
+        Self::from_fields(self.ident.clone(), fields)
+    }
 }
 
 #[allow(unused)]
 impl IssueData {
     pub(crate) fn new_empty(ident: String) -> Self {
-        Self::from_raw_data(ident, RawIssueData::default())
+        Self::from_fields(ident, IssueFields::default())
     }
 
     fn from_raw_data(ident: String, value: RawIssueData) -> Self {
-        Self {
-            ident,
-            key: initialize_lazy_field!(value.key),
-            summary: initialize_lazy_field!(value.summary),
-            description: initialize_lazy_field!(value.description),
-            comments: initialize_lazy_field!(
-                value.comments.map(|v| v.into_iter().map(|c| c.body).collect())
-            ),
-            status: initialize_lazy_field!(value.status.map(|x| x.name)),
-            resolution: initialize_lazy_field!(
-                value.resolution.map(|x| x.map(|y| y.name))
-            ),
-            priority: initialize_lazy_field!(value.priority.map(|x| x.name)),
-            issue_type: initialize_lazy_field!(value.issuetype.map(|x| x.name)),
-            issue_links: initialize_lazy_field!(Some(vec![])),
-            parent: initialize_lazy_field!(value.parent),
-            subtasks: initialize_lazy_field!(value.subtasks),
-            watches: initialize_lazy_field!(value.watches.map(|x| x.watch_count)),
-            votes: initialize_lazy_field!(value.votes.map(|x| x.votes)),
-            date_created: initialize_lazy_field!(value.created),
-            date_updated: initialize_lazy_field!(value.updated),
-            date_resolved: initialize_lazy_field!(value.resolutiondate),
-            labels: initialize_lazy_field!(value.labels),
-            components: initialize_lazy_field!(
-                value.components.map(|v| v.into_iter().map(|c| c.name).collect())
-            ),
-            affected_versions: initialize_lazy_field!(
-                value.versions.map(|v| v.into_iter().map(|c| c.name).collect())
-            ),
-            fix_versions: initialize_lazy_field!(
-                value.fix_versions.map(|v| v.into_iter().map(|c| c.name).collect())
-            )
+        Self::from_fields(ident, IssueFields::from_raw_data(value))
+    }
+
+    pub(crate) fn from_fields(ident: String, fields: IssueFields) -> Self {
+        Self{ident, fields: RwLock::new(fields), group: RwLock::new(None)}
+    }
+
+    fn read(&self) -> APIResult<std::sync::RwLockReadGuard<'_, IssueFields>> {
+        Ok(self.fields.read().map_err(|_| CacheLockError{})?)
+    }
+
+    fn group(&self) -> APIResult<Option<Arc<LoadingGroup>>> {
+        Ok(self.group.read().map_err(|_| CacheLockError{})?.clone())
+    }
+
+    pub(crate) fn join_group(&self, group: Arc<LoadingGroup>) {
+        if let Ok(mut g) = self.group.write() {
+            let _ = g.get_or_insert(group);
         }
     }
 
-    pub(crate) fn update(&self, other: IssueData) {
-        maybe_copy_attribute!(self, other, key);
-        maybe_copy_attribute!(self, other, summary);
-        maybe_copy_attribute!(self, other, description);
-        maybe_copy_attribute!(self, other, comments);
-        maybe_copy_attribute!(self, other, parent);
-        maybe_copy_attribute!(self, other, subtasks);
-        maybe_copy_attribute!(self, other, issue_links);
-        maybe_copy_attribute!(self, other, status);
-        maybe_copy_attribute!(self, other, priority);
-        maybe_copy_attribute!(self, other, resolution);
-        maybe_copy_attribute!(self, other, issue_type);
-        maybe_copy_attribute!(self, other, watches);
-        maybe_copy_attribute!(self, other, votes);
-        maybe_copy_attribute!(self, other, date_created);
-        maybe_copy_attribute!(self, other, date_updated);
-        maybe_copy_attribute!(self, other, date_resolved);
-        maybe_copy_attribute!(self, other, labels);
-        maybe_copy_attribute!(self, other, components);
-        maybe_copy_attribute!(self, other, affected_versions);
-        maybe_copy_attribute!(self, other, fix_versions);
+    pub(crate) fn ident(&self) -> &String {
+        &self.ident
+    }
+
+    pub(crate) fn is_loaded(&self, attribute: IssueAttribute) -> bool {
+        self.fields
+            .read()
+            .map(|f| f.is_loaded(attribute))
+            .unwrap_or(false)
     }
 
-    pub fn key(&self, api: &IssueAPI) -> APIResult<&String> {
-        load_lazy_attribute!(self, key, IssueAttribute::Key, api)
+    /// Merge the data from `other` into this object.
+    /// Attributes which are already loaded are left untouched.
+    pub(crate) fn update(&self, other: IssueData) -> APIResult<()> {
+        let other_fields = other.fields
+            .into_inner()
+            .map_err(|_| CacheLockError{})?;
+        self.fields
+            .write()
+            .map_err(|_| CacheLockError{})?
+            .merge(other_fields);
+        Ok(())
+    }
+
+    /// Copy of all attributes loaded so far.
+    pub fn fields(&self) -> APIResult<IssueFields> {
+        Ok(self.read()?.clone())
+    }
+
+    pub fn key(&self, api: &IssueAPI) -> APIResult<String> {
+        load_attribute!(self, key, IssueAttribute::Key, api)
     }
 
-    pub fn summary(&self, api: &IssueAPI) -> APIResult<&String> {
-        load_lazy_attribute!(self, summary, IssueAttribute::Summary, api)
+    pub fn summary(&self, api: &IssueAPI) -> APIResult<String> {
+        load_attribute!(self, summary, IssueAttribute::Summary, api)
     }
 
-    pub fn description(&self, api: &IssueAPI) -> APIResult<&String> {
-        load_lazy_attribute!(self, description, IssueAttribute::Description, api)
+    pub fn description(&self, api: &IssueAPI) -> APIResult<String> {
+        load_attribute!(self, description, IssueAttribute::Description, api)
     }
 
-    pub fn comments(&self, api: &IssueAPI) -> APIResult<&Vec<String>> {
-        load_lazy_attribute!(self, comments, IssueAttribute::Comments, api)
+    pub fn comments(&self, api: &IssueAPI) -> APIResult<Vec<String>> {
+        load_attribute!(self, comments, IssueAttribute::Comments, api)
     }
 
-    pub fn parent(&self, api: &IssueAPI) -> APIResult<&Option<String>> {
-        load_lazy_attribute!(self, parent, IssueAttribute::Parent, api)
+    pub fn parent(&self, api: &IssueAPI) -> APIResult<Option<String>> {
+        load_attribute!(self, parent, IssueAttribute::Parent, api)
     }
 
-    pub fn subtasks(&self, api: &IssueAPI) -> APIResult<&Vec<String>> {
-        load_lazy_attribute!(self, subtasks, IssueAttribute::Subtasks, api)
+    pub fn subtasks(&self, api: &IssueAPI) -> APIResult<Vec<String>> {
+        load_attribute!(self, subtasks, IssueAttribute::Subtasks, api)
     }
 
-    pub fn issue_links(&self, api: &IssueAPI) -> APIResult<&Vec<String>> {
-        load_lazy_attribute!(self, issue_links, IssueAttribute::IssueLinks, api)
+    pub fn issue_links(&self, api: &IssueAPI) -> APIResult<Vec<String>> {
+        load_attribute!(self, issue_links, IssueAttribute::IssueLinks, api)
     }
 
-    pub fn status(&self, api: &IssueAPI) -> APIResult<&String> {
-        load_lazy_attribute!(self, status, IssueAttribute::Status, api)
+    pub fn status(&self, api: &IssueAPI) -> APIResult<String> {
+        load_attribute!(self, status, IssueAttribute::Status, api)
     }
 
-    pub fn priority(&self, api: &IssueAPI) -> APIResult<&String> {
-        load_lazy_attribute!(self, priority, IssueAttribute::Priority, api)
+    pub fn priority(&self, api: &IssueAPI) -> APIResult<String> {
+        load_attribute!(self, priority, IssueAttribute::Priority, api)
     }
 
-    pub fn resolution(&self, api: &IssueAPI) -> APIResult<&Option<String>> {
-        load_lazy_attribute!(self, resolution, IssueAttribute::Resolution, api)
+    pub fn resolution(&self, api: &IssueAPI) -> APIResult<Option<String>> {
+        load_attribute!(self, resolution, IssueAttribute::Resolution, api)
     }
 
-    pub fn issue_type(&self, api: &IssueAPI) -> APIResult<&String> {
-        load_lazy_attribute!(self, issue_type, IssueAttribute::IssueType, api)
+    pub fn issue_type(&self, api: &IssueAPI) -> APIResult<String> {
+        load_attribute!(self, issue_type, IssueAttribute::IssueType, api)
     }
 
     pub fn watches(&self, api: &IssueAPI) -> APIResult<u64> {
-        load_lazy_attribute!(self, watches, IssueAttribute::Watches, api).map(|x| *x)
+        load_attribute!(self, watches, IssueAttribute::Watches, api)
     }
 
     pub fn votes(&self, api: &IssueAPI) -> APIResult<u64> {
-        load_lazy_attribute!(self, votes, IssueAttribute::Votes, api).map(|x| *x)
+        load_attribute!(self, votes, IssueAttribute::Votes, api)
     }
 
-    pub fn date_created(&self, api: &IssueAPI) -> APIResult<&String> {
-        load_lazy_attribute!(self, date_created, IssueAttribute::DateCreated, api)
+    pub fn date_created(&self, api: &IssueAPI) -> APIResult<String> {
+        load_attribute!(self, date_created, IssueAttribute::DateCreated, api)
     }
 
-    pub fn date_updated(&self, api: &IssueAPI) -> APIResult<&String> {
-        load_lazy_attribute!(self, date_updated, IssueAttribute::DateUpdated, api)
+    pub fn date_updated(&self, api: &IssueAPI) -> APIResult<String> {
+        load_attribute!(self, date_updated, IssueAttribute::DateUpdated, api)
     }
 
-    pub fn date_resolved(&self, api: &IssueAPI) -> APIResult<&Option<String>> {
-        load_lazy_attribute!(self, date_resolved, IssueAttribute::DateResolved, api)
+    pub fn date_resolved(&self, api: &IssueAPI) -> APIResult<Option<String>> {
+        load_attribute!(self, date_resolved, IssueAttribute::DateResolved, api)
     }
 
-    pub fn labels(&self, api: &IssueAPI) -> APIResult<&Vec<String>> {
-        load_lazy_attribute!(self, labels, IssueAttribute::Labels, api)
+    pub fn labels(&self, api: &IssueAPI) -> APIResult<Vec<String>> {
+        load_attribute!(self, labels, IssueAttribute::Labels, api)
     }
 
-    pub fn components(&self, api: &IssueAPI) -> APIResult<&Vec<String>> {
-        load_lazy_attribute!(self, components, IssueAttribute::Components, api)
+    pub fn components(&self, api: &IssueAPI) -> APIResult<Vec<String>> {
+        load_attribute!(self, components, IssueAttribute::Components, api)
     }
 
-    pub fn affected_versions(&self, api: &IssueAPI) -> APIResult<&Vec<String>> {
-        load_lazy_attribute!(self, affected_versions, IssueAttribute::AffectedVersions, api)
+    pub fn affected_versions(&self, api: &IssueAPI) -> APIResult<Vec<String>> {
+        load_attribute!(self, affected_versions, IssueAttribute::AffectedVersions, api)
     }
 
-    pub fn fix_versions(&self, api: &IssueAPI) -> APIResult<&Vec<String>> {
-        load_lazy_attribute!(self, fix_versions, IssueAttribute::FixVersions, api)
+    pub fn fix_versions(&self, api: &IssueAPI) -> APIResult<Vec<String>> {
+        load_attribute!(self, fix_versions, IssueAttribute::FixVersions, api)
     }
 }
 
@@ -288,7 +461,15 @@ impl IssueAPI {
                 url,
                 token: None,
                 client,
-                allow_unsafe_ssl: allow_self_signed
+                allow_unsafe_ssl: allow_self_signed,
+                metrics: Arc::new(MetricsCollector::new()),
+                throttle: Arc::new(RequestThrottle::new()),
+                issue_data_batching: Arc::new(IssueDataBatching{
+                    batch_size: AtomicUsize::new(DEFAULT_ISSUE_DATA_BATCH_SIZE),
+                    workers: AtomicUsize::new(DEFAULT_ISSUE_DATA_WORKERS)
+                }),
+                label_schemas: Arc::new(LabelSchemaRegistry::new()),
+                model_config_schema: Arc::new(ConfigSchemaSlot::default())
             }
         )
     }
@@ -348,19 +529,95 @@ impl IssueAPI {
         Ok(request_base)
     }
 
-    fn handle_error_status(&self,
-                           response: reqwest::blocking::Response) -> APIResult<reqwest::blocking::Response> {
-        Ok(response.error_for_status()?)
+    pub(crate) fn url(&self) -> &String {
+        &self.url
+    }
+
+    pub(crate) fn allows_self_signed_certificates(&self) -> bool {
+        self.allow_unsafe_ssl
+    }
+
+    pub(crate) fn metrics(&self) -> Arc<MetricsCollector> {
+        self.metrics.clone()
+    }
+
+    pub(crate) fn throttle(&self) -> &RequestThrottle {
+        &self.throttle
+    }
+
+    pub(crate) fn label_schemas(&self) -> &LabelSchemaRegistry {
+        &self.label_schemas
     }
 
-    fn unpack_response<O>(&self, response: reqwest::blocking::Response) -> APIResult<O>
+    pub(crate) fn model_config_schema(&self) -> &ConfigSchemaSlot {
+        &self.model_config_schema
+    }
+
+    pub(crate) fn set_issue_data_batching(&self, batch_size: usize, workers: usize) -> APIResult<()> {
+        if batch_size == 0 || workers == 0 {
+            let msg = "Batch size and number of workers must be positive".to_string();
+            return Err(APIError::GenericError(msg));
+        }
+        self.issue_data_batching.batch_size.store(batch_size, Ordering::Relaxed);
+        self.issue_data_batching.workers.store(workers, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Send a request and decode the JSON response.
+    /// Every request is wrapped in a tracing span and
+    /// accounted for in the metrics collector.
+    fn execute<O>(&self,
+                  suffix: &str,
+                  verb: Verb,
+                  request: reqwest::blocking::RequestBuilder) -> APIResult<O>
     where
         O: for <'de> serde::Deserialize<'de>
     {
-        let result = self.handle_error_status(response)?
-            .json::<O>()
-            .expect("Received invalid response from server.");
-        Ok(result)
+        let request = request.build()?;
+        // Streaming (e.g. file-backed multipart) bodies have no known size.
+        let payload_size = request.body()
+            .and_then(|b| b.as_bytes())
+            .map(|b| b.len() as u64)
+            .unwrap_or(0);
+        let span = tracing::info_span!(
+            "issue_api_request",
+            endpoint = suffix,
+            verb = %verb,
+            payload_size,
+            status = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+            throttle_ms = tracing::field::Empty,
+            retries = 0u32,     // the client currently never retries requests
+        );
+        let _entered = span.enter();
+        let waiting = Instant::now();
+        let _permit = self.throttle.acquire()?;
+        span.record("throttle_ms", waiting.elapsed().as_millis() as u64);
+        let start = Instant::now();
+        let outcome = match self.client.execute(request) {
+            Ok(response) => {
+                span.record("status", response.status().as_u16());
+                response.error_for_status().and_then(|r| r.bytes())
+            },
+            Err(e) => Err(e)
+        };
+        let latency = start.elapsed();
+        span.record("latency_ms", latency.as_millis() as u64);
+        let bytes_received = outcome.as_ref().map(|b| b.len() as u64).unwrap_or(0);
+        self.metrics.record_request(payload_size, bytes_received, latency, outcome.is_ok());
+        match outcome {
+            Ok(body) => {
+                tracing::debug!(bytes_received, "request completed");
+                serde_json::from_slice::<O>(body.as_ref()).map_err(|e| {
+                    tracing::warn!(error = %e, "received invalid response from server");
+                    APIError::LibraryError(format!("Received invalid response from server: {e}"))
+                })
+            },
+            Err(e) => {
+                tracing::warn!(error = %e, "request failed");
+                Err(e.into())
+            }
+        }
     }
 
     fn call_endpoint_json<I, O>(&self,
@@ -371,11 +628,9 @@ impl IssueAPI {
         I: serde::Serialize + std::fmt::Debug,
         O: for <'de> serde::Deserialize<'de>,
     {
-        let response = self.build_request_base(suffix, verb)?
-            .json(&payload)
-            .send()?;
-        let result = self.unpack_response(response)?;
-        Ok(result)
+        let request = self.build_request_base(suffix, verb)?
+            .json(&payload);
+        self.execute(suffix, verb, request)
     }
 
     fn call_endpoint_form<I, O>(&self,
@@ -386,11 +641,9 @@ impl IssueAPI {
             I: serde::Serialize + ?Sized,
             O: for <'de> serde::Deserialize<'de>,
     {
-        let response = self.build_request_base(suffix, verb)?
-            .form(payload)
-            .send()?;
-        let result = self.unpack_response(response)?;
-        Ok(result)
+        let request = self.build_request_base(suffix, verb)?
+            .form(payload);
+        self.execute(suffix, verb, request)
     }
 
     fn call_endpoint_multipart<O>(&self,
@@ -400,11 +653,9 @@ impl IssueAPI {
         where
             O: for <'de> serde::Deserialize<'de>,
     {
-        let response = self.build_request_base(suffix, verb)?
-            .multipart(payload)
-            .send()?;
-        let result = self.unpack_response(response)?;
-        Ok(result)
+        let request = self.build_request_base(suffix, verb)?
+            .multipart(payload);
+        self.execute(suffix, verb, request)
     }
 
     fn call_endpoint_multipart_object<I, O>(&self,
@@ -418,7 +669,20 @@ impl IssueAPI {
         let mut buffer = Cursor::new(Vec::new());
         serde_json::to_writer(&mut buffer, payload)?;
         buffer.set_position(0);
-        let part = multipart::Part::reader(buffer)
+        self.call_endpoint_multipart_reader(suffix, verb, buffer)
+    }
+
+    /// Upload the JSON document produced by `reader` as a file,
+    /// without buffering it in memory first.
+    fn call_endpoint_multipart_reader<R, O>(&self,
+                                            suffix: &str,
+                                            verb: Verb,
+                                            reader: R) -> APIResult<O>
+        where
+            R: std::io::Read + Send + 'static,
+            O: for <'de> serde::Deserialize<'de>,
+    {
+        let part = multipart::Part::reader(reader)
             .mime_str("application/json")?
             .file_name("file.json");
         let form = multipart::Form::new().part("file", part);
@@ -434,7 +698,58 @@ impl IssueAPI {
             I: serde::Serialize,
     {
         let mut file = std::fs::File::create(target_path)?;
-        self.call_endpoint_download_into(suffix, verb, payload, &mut file)
+        self.call_endpoint_download_into(suffix, verb, payload, &mut file, 0, None)?
+            .into_result(suffix)
+    }
+
+    /// Download into a partial file next to `target_path`, which is kept
+    /// if the download fails, so that the next download can resume from it.
+    /// Downloads are only resumed if a checksum is given, in which case
+    /// the file is only moved to `target_path` if its contents match
+    /// the checksum. Without a checksum, any partial file is discarded.
+    fn call_endpoint_download_resumable<I>(&self,
+                                           suffix: &str,
+                                           verb: Verb,
+                                           payload: I,
+                                           target_path: String,
+                                           expected_sha256: Option<&str>,
+                                           progress: Option<&ProgressCallback>) -> APIResult<()>
+        where
+            I: serde::Serialize,
+    {
+        let partial = partial_download_path(&target_path, suffix);
+        if expected_sha256.is_none() {
+            // Without a checksum, a resumed download could never be verified
+            match std::fs::remove_file(&partial) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&partial)?;
+        let offset = file.metadata()?.len();
+        let outcome = self.call_endpoint_download_into(
+            suffix, verb, &payload, &mut file, offset, progress
+        )?;
+        if outcome == DownloadOutcome::Mismatch {
+            tracing::warn!(partial = partial.as_str(), "partial download does not match; restarting");
+            file.set_len(0)?;
+            self.call_endpoint_download_into(suffix, verb, &payload, &mut file, 0, progress)?
+                .into_result(suffix)?;
+        }
+        drop(file);
+        if let Some(expected) = expected_sha256 {
+            let actual = sha256_file(&partial)?;
+            if !actual.eq_ignore_ascii_case(expected) {
+                // Resuming from corrupt data can never succeed
+                std::fs::remove_file(&partial)?;
+                return Err(APIError::ChecksumMismatch{expected: expected.to_string(), actual});
+            }
+        }
+        std::fs::rename(partial, target_path)?;
+        Ok(())
     }
 
     fn call_endpoint_download_object<I, O>(&self,
@@ -446,17 +761,31 @@ impl IssueAPI {
             O: for <'de> serde::Deserialize<'de>
     {
         let mut buffer = std::io::Cursor::new(Vec::new());
-        self.call_endpoint_download_into(suffix, verb, payload, &mut buffer)?;
+        self.call_endpoint_download_into(suffix, verb, payload, &mut buffer, 0, None)?
+            .into_result(suffix)?;
         buffer.set_position(0);
         let result: O = serde_json::from_reader(buffer)?;
         Ok(result)
     }
 
+    /// Stream the response body into `sink`. The first `offset` bytes are
+    /// assumed to be in the sink already, and are skipped using an HTTP range
+    /// request; if the server ignores the range, they are skipped client-side.
+    /// When the connection fails, the download is resumed in the same way,
+    /// up to `MAX_DOWNLOAD_ATTEMPTS` times.
+    ///
+    /// A range response is only used if it starts at the current position,
+    /// and its total size matches the size reported earlier during this
+    /// download; a 416 response is only accepted if the total size equals
+    /// the number of bytes in the sink. Otherwise, the sink does not hold
+    /// a prefix of the resource, and `DownloadOutcome::Mismatch` is returned.
     fn call_endpoint_download_into<I, S>(&self,
                                          suffix: &str,
                                          verb: Verb,
                                          payload: I,
-                                         sink: &mut S) -> APIResult<()>
+                                         sink: &mut S,
+                                         offset: u64,
+                                         progress: Option<&ProgressCallback>) -> APIResult<DownloadOutcome>
         where
             I: serde::Serialize,
             S: std::io::Write
@@ -467,19 +796,96 @@ impl IssueAPI {
             .timeout(READ_WRITE_TIMEOUT)
             .connect_timeout(CONNECT_TIMEOUT)
             .build()?;
+        let span = tracing::info_span!(
+            "issue_api_download",
+            endpoint = suffix,
+            verb = %verb,
+            offset,
+            status = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+            bytes_received = tracing::field::Empty,
+            throttle_ms = tracing::field::Empty,
+            retries = 0u32,
+        );
+        let _entered = span.enter();
+        let waiting = Instant::now();
+        let _permit = self.throttle.acquire()?;
+        span.record("throttle_ms", waiting.elapsed().as_millis() as u64);
+        let start = Instant::now();
+        let mut bytes_received: u64 = 0;
+        let mut position = offset;
+        let mut expected_total: Option<u64> = None;
+        let mut retries: u32 = 0;
         let rt = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
-        let result: APIResult<()> = rt.block_on(async {
-            let mut stream = client
-                .get(url)
-                .json(&payload)
-                .send()
-                .await?
-                .error_for_status()?;
-            while let Some(chunk) = stream.chunk().await? {
-                sink.write_all(chunk.as_ref())?;
+        let result: APIResult<DownloadOutcome> = loop {
+            // Errors are paired with whether the download can be resumed after them
+            let attempt: Result<DownloadOutcome, (APIError, bool)> = rt.block_on(async {
+                let mut request = client.get(url.as_str()).json(&payload);
+                if position > 0 {
+                    request = request.header(reqwest::header::RANGE, format!("bytes={position}-"));
+                }
+                let response = request.send().await.map_err(|e| (e.into(), true))?;
+                let status = response.status();
+                span.record("status", status.as_u16());
+                if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE && position > 0 {
+                    // Everything has been received already, if the sizes agree
+                    let total = content_range(response.headers()).and_then(|r| r.total);
+                    return if total == Some(position) && expected_total.unwrap_or(position) == position {
+                        Ok(DownloadOutcome::Complete)
+                    } else {
+                        Ok(DownloadOutcome::Mismatch)
+                    };
+                }
+                let mut stream = response.error_for_status().map_err(|e| (e.into(), false))?;
+                let (mut skip, total) = if status == reqwest::StatusCode::PARTIAL_CONTENT {
+                    let range = content_range(stream.headers());
+                    if range.and_then(|r| r.start) != Some(position) {
+                        return Ok(DownloadOutcome::Mismatch);
+                    }
+                    (0, range.and_then(|r| r.total))
+                } else {
+                    (position, stream.content_length())
+                };
+                match (expected_total, total) {
+                    (Some(expected), Some(total)) if expected != total => {
+                        return Ok(DownloadOutcome::Mismatch);
+                    },
+                    (_, Some(total)) if total < position => return Ok(DownloadOutcome::Mismatch),
+                    (None, Some(total)) => expected_total = Some(total),
+                    _ => {}
+                }
+                while let Some(chunk) = stream.chunk().await.map_err(|e| (e.into(), true))? {
+                    bytes_received += chunk.len() as u64;
+                    let skipped = skip.min(chunk.len() as u64);
+                    skip -= skipped;
+                    let data = &chunk[skipped as usize..];
+                    sink.write_all(data).map_err(|e| (e.into(), false))?;
+                    position += data.len() as u64;
+                    if let Some(progress) = progress {
+                        progress(position, total);
+                    }
+                }
+                Ok(DownloadOutcome::Complete)
+            });
+            match attempt {
+                Ok(outcome) => break Ok(outcome),
+                Err((e, true)) if retries + 1 < MAX_DOWNLOAD_ATTEMPTS => {
+                    retries += 1;
+                    span.record("retries", retries);
+                    tracing::warn!(error = %e, position, "download interrupted; resuming");
+                    std::thread::sleep(DOWNLOAD_RETRY_DELAY * retries);
+                },
+                Err((e, _)) => break Err(e)
             }
-            Ok(())
-        });
+        };
+        let latency = start.elapsed();
+        span.record("latency_ms", latency.as_millis() as u64);
+        span.record("bytes_received", bytes_received);
+        let succeeded = matches!(result, Ok(DownloadOutcome::Complete));
+        self.metrics.record_request(0, bytes_received, latency, succeeded);
+        if let Err(ref e) = result {
+            tracing::warn!(error = %e, "download failed");
+        }
         result
     }
 
@@ -547,9 +953,52 @@ impl IssueAPI {
         Ok(ids)
     }
 
+    /// Fetch issue data in batches. Batches are requested concurrently
+    /// by a small pool of worker threads; all requests still pass
+    /// through the request throttle.
     pub(crate) fn get_issue_data(&self,
                           issues: Vec<String>,
                           attributes: Vec<IssueAttribute>) -> APIResult<HashMap<String, IssueData>> {
+        let batch_size = self.issue_data_batching.batch_size.load(Ordering::Relaxed);
+        if issues.len() <= batch_size {
+            return self.get_issue_data_batch(issues, attributes);
+        }
+        let batches = issues.chunks(batch_size).collect::<Vec<_>>();
+        let workers = self.issue_data_batching.workers
+            .load(Ordering::Relaxed)
+            .min(batches.len());
+        let next_batch = AtomicUsize::new(0);
+        let result: Mutex<APIResult<HashMap<String, IssueData>>> = Mutex::new(
+            Ok(HashMap::with_capacity(issues.len()))
+        );
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    let index = next_batch.fetch_add(1, Ordering::Relaxed);
+                    if index >= batches.len() {
+                        break;
+                    }
+                    let data = self.get_issue_data_batch(
+                        batches[index].to_vec(), attributes.clone()
+                    );
+                    let mut guard = result.lock().expect("Poisoned lock in issue data loading");
+                    match (guard.as_mut(), data) {
+                        (Ok(merged), Ok(batch)) => merged.extend(batch),
+                        (Ok(_), Err(e)) => {
+                            *guard = Err(e);
+                            break;
+                        }
+                        (Err(_), _) => break
+                    }
+                });
+            }
+        });
+        result.into_inner().map_err(|_| CacheLockError{})?
+    }
+
+    fn get_issue_data_batch(&self,
+                            issues: Vec<String>,
+                            attributes: Vec<IssueAttribute>) -> APIResult<HashMap<String, IssueData>> {
         let mut map = Map::new();
         map.insert(
             "issue_ids".to_string(),
@@ -591,10 +1040,16 @@ impl IssueAPI {
         Ok(result.tags)
     }
 
-    pub(crate) fn register_new_tag(&self, name: String, description: String) -> APIResult<()> {
+    pub(crate) fn register_new_tag(&self,
+                                   name: String,
+                                   description: String,
+                                   tag_type: Option<TagType>) -> APIResult<()> {
         let mut map = Map::new();
         map.insert("tag".to_string(), Value::String(name));
         map.insert("description".to_string(), Value::String(description));
+        if let Some(tag_type) = tag_type {
+            map.insert("type".to_string(), Value::String(tag_type.into()));
+        }
         self.call_endpoint_json("tags", Verb::Post, Value::Object(map))?;
         Ok(())
     }
@@ -607,7 +1062,7 @@ impl IssueAPI {
         let endpoint = format!("tags/{}", tag);
         let result = self.call_endpoint_json::<_, TagInfoResponse>(
             endpoint.as_str(), Verb::Get, Value::Object(Map::new())
-        )?;
+        ).map_err(|e| e.not_found(|| format!("tag {tag}")))?;
         Ok(result.tag)
     }
 
@@ -633,22 +1088,63 @@ impl IssueAPI {
      * Tag + Issue endpoints
      */
 
-    pub(crate) fn start_issue_review(&self, issue_id: String) -> APIResult<()> {
+    pub(crate) fn start_issue_review(&self, issue_id: String, reason: Option<String>) -> APIResult<()> {
         let endpoint = format!("issues/{}/mark-review", issue_id);
-        self.call_endpoint_json(
-            endpoint.as_str(), Verb::Post, Value::Object(Map::new())
+        let mut map = Map::new();
+        if let Some(reason) = reason {
+            map.insert("reason".to_string(), Value::String(reason));
+        }
+        self.call_endpoint_json::<_, ()>(
+            endpoint.as_str(), Verb::Post, Value::Object(map)
         )?;
         Ok(())
     }
 
-    pub(crate) fn finish_issue_review(&self, issue_id: String) -> APIResult<()> {
+    pub(crate) fn finish_issue_review(&self, issue_id: String, outcome: Map<String, Value>) -> APIResult<()> {
         let endpoint = format!("issues/{}/finish-review", issue_id);
-        self.call_endpoint_json(
-            endpoint.as_str(), Verb::Post, Value::Object(Map::new())
+        self.call_endpoint_json::<_, ()>(
+            endpoint.as_str(), Verb::Post, Value::Object(outcome)
         )?;
         Ok(())
     }
 
+    pub(crate) fn get_issues_in_review(&self) -> APIResult<Vec<UnboundReview>> {
+        #[derive(Debug, serde::Deserialize)]
+        struct ReviewsResponse {
+            reviews: Vec<UnboundReview>
+        }
+        let result = self.call_endpoint_json::<_, ReviewsResponse>(
+            "issues/in-review", Verb::Get, Value::Object(Map::new())
+        )?;
+        Ok(result.reviews)
+    }
+
+    pub(crate) fn bulk_start_review(&self, issues: Vec<String>, reason: Option<String>) -> APIResult<()> {
+        let mut map = Map::new();
+        map.insert("issue_ids".to_string(),
+                   Value::Array(issues.into_iter().map(Value::String).collect()));
+        if let Some(reason) = reason {
+            map.insert("reason".to_string(), Value::String(reason));
+        }
+        self.call_endpoint_json(
+            "bulk/mark-review", Verb::Post, Value::Object(map)
+        )
+    }
+
+    pub(crate) fn bulk_finish_review(&self, outcomes: HashMap<String, Map<String, Value>>) -> APIResult<()> {
+        let data = outcomes
+            .into_iter()
+            .map(|(issue_id, mut outcome)| {
+                outcome.insert("issue_id".to_string(), Value::String(issue_id));
+                Value::Object(outcome)
+            }).collect::<Vec<_>>();
+        let mut map = Map::new();
+        map.insert("data".to_string(), Value::Array(data));
+        self.call_endpoint_json(
+            "bulk/finish-review", Verb::Post, Value::Object(map)
+        )
+    }
+
     pub(crate) fn get_tags_for_issue(&self, issue_id: String) -> APIResult<Vec<String>> {
         #[derive(Debug, serde::Deserialize)]
         struct TagsResponse {
@@ -680,6 +1176,16 @@ impl IssueAPI {
     }
 
     pub(crate) fn bulk_add_tags(&self, issues_and_tags: HashMap<String, Vec<String>>) -> APIResult<()> {
+        self.bulk_update_tags("bulk/add-tags", issues_and_tags)
+    }
+
+    pub(crate) fn bulk_remove_tags(&self, issues_and_tags: HashMap<String, Vec<String>>) -> APIResult<()> {
+        self.bulk_update_tags("bulk/remove-tags", issues_and_tags)
+    }
+
+    fn bulk_update_tags(&self,
+                        endpoint: &str,
+                        issues_and_tags: HashMap<String, Vec<String>>) -> APIResult<()> {
         let maps = issues_and_tags
             .into_iter()
             .map(|(key, value)| {
@@ -691,9 +1197,7 @@ impl IssueAPI {
             }).collect::<Vec<_>>();
         let mut map = Map::new();
         map.insert("data".to_string(), Value::Array(maps));
-        self.call_endpoint_json(
-            "/bulk/add-tags", Verb::Post, Value::Object(map)
-        )
+        self.call_endpoint_json(endpoint, Verb::Post, Value::Object(map))
     }
 
     /***************************************************************************
@@ -701,11 +1205,6 @@ impl IssueAPI {
      */
 
     pub(crate) fn get_labeling_comments_for_issue(&self, issue_id: String) -> APIResult<Vec<UnboundComment>> {
-        #[derive(Debug, serde::Deserialize)]
-        struct RawComment {
-            author: String,
-            comment: String
-        }
         #[derive(Debug, serde::Deserialize)]
         struct CommentsResponse {
             comments: HashMap<String, RawComment>
@@ -714,30 +1213,73 @@ impl IssueAPI {
         let result = self.call_endpoint_json::<_, CommentsResponse>(
             endpoint.as_str(), Verb::Get, Value::Object(Map::new())
         )?;
-        // collect converted comments
-        let converted: Vec<UnboundComment> = result.comments.into_iter()
-            .map(|(id, raw)| UnboundComment{id, author: raw.author, text: raw.comment})
-            .collect();
-        // Get object IDS in order to restore comment order
-        let mut object_ids: Vec<u128> = Vec::with_capacity(converted.len());
-        for c in converted.iter() {
-            // let id = c.id
-            //     .parse::<u128>()
-            //     .map_err(|e| IDParsingError{msg: e.to_string()})?;
-            let id = u128::from_str_radix(c.id.as_str(), 16)
-                .map_err(|e| APIError::IDParsingError(e.to_string()))?;
-            object_ids.push(id);
+        convert_raw_comments(result.comments)
+    }
+
+    /// Comments for many issues at once, keyed by issue ID.
+    /// Servers which cannot return comments for many issues at once
+    /// are handled by fetching the comments of every issue separately.
+    pub(crate) fn get_labeling_comments_for_issues(&self,
+                                                   issues: Vec<String>) -> APIResult<HashMap<String, Vec<UnboundComment>>> {
+        #[derive(Debug, serde::Deserialize)]
+        struct CommentsResponse {
+            comments: HashMap<String, HashMap<String, RawComment>>
+        }
+        let mut map = Map::new();
+        map.insert("issue_ids".to_string(),
+                   Value::Array(issues.iter().cloned().map(Value::String).collect()));
+        let result = self.call_endpoint_json::<_, CommentsResponse>(
+            "manual-labels/comments", Verb::Get, map
+        );
+        let result = match result {
+            Ok(result) => result,
+            Err(APIError::HTTPError{status_code: 404 | 405 | 501, ..}) => {
+                return issues.into_iter()
+                    .map(|id| {
+                        let comments = self.get_labeling_comments_for_issue(id.clone())?;
+                        Ok((id, comments))
+                    })
+                    .collect();
+            },
+            Err(e) => return Err(e)
+        };
+        let mut comments = HashMap::with_capacity(result.comments.len());
+        for (issue_id, raw) in result.comments {
+            comments.insert(issue_id, convert_raw_comments(raw)?);
         }
-        // Sort comments
-        let mut pairs = object_ids.into_iter()
-            .zip(converted.into_iter()).collect::<Vec<_>>();
-        pairs.sort_by_key(|p| p.0);
-        // Extract comments without ids
-        let (_, comments): (Vec<u128>, Vec<UnboundComment>) = pairs.into_iter().unzip();
         Ok(comments)
     }
 
-    pub(crate) fn add_labeling_comment_to_issue(&self, issue_id: String, text: String) -> APIResult<String> {
+    /// Server-side comment search. Returns `None` if the server
+    /// does not support it.
+    pub(crate) fn search_labeling_comments(&self,
+                                           query: Query,
+                                           filter: Map<String, Value>) -> APIResult<Option<HashMap<String, Vec<UnboundComment>>>> {
+        #[derive(Debug, serde::Deserialize)]
+        struct SearchResponse {
+            comments: HashMap<String, HashMap<String, RawComment>>
+        }
+        let mut map = filter;
+        map.insert("filter".to_string(), query.into_json());
+        let result = self.call_endpoint_json::<_, SearchResponse>(
+            "manual-labels/comments/search", Verb::Get, map
+        );
+        let response = match result {
+            Ok(response) => response,
+            Err(APIError::HTTPError{status_code: 404 | 405 | 501, ..}) => return Ok(None),
+            Err(e) => return Err(e)
+        };
+        let mut comments = HashMap::with_capacity(response.comments.len());
+        for (issue_id, raw) in response.comments {
+            comments.insert(issue_id, convert_raw_comments(raw)?);
+        }
+        Ok(Some(comments))
+    }
+
+    pub(crate) fn add_labeling_comment_to_issue(&self,
+                                                issue_id: String,
+                                                text: String,
+                                                reply_to: Option<String>) -> APIResult<String> {
         #[derive(Debug, serde::Deserialize)]
         struct NewCommentResponse {
             comment_id: String
@@ -745,6 +1287,9 @@ impl IssueAPI {
         let endpoint = format!("manual-labels/{}/comments", issue_id);
         let mut map = Map::new();
         map.insert("comment".to_string(), Value::String(text));
+        if let Some(parent) = reply_to {
+            map.insert("reply_to".to_string(), Value::String(parent));
+        }
         let result = self.call_endpoint_json::<_, NewCommentResponse>(
             endpoint.as_str(), Verb::Post, Value::Object(map)
         )?;
@@ -758,7 +1303,20 @@ impl IssueAPI {
         let endpoint = format!("manual-labels/{}/comments/{}", issue_id, comment_id);
         let mut map = Map::new();
         map.insert("comment".to_string(), Value::String(new_text));
-        self.call_endpoint_json(
+        self.call_endpoint_json::<_, ()>(
+            endpoint.as_str(), Verb::Patch, Value::Object(map)
+        )?;
+        Ok(())
+    }
+
+    pub(crate) fn set_labeling_comment_resolved(&self,
+                                                issue_id: String,
+                                                comment_id: String,
+                                                resolved: bool) -> APIResult<()> {
+        let endpoint = format!("manual-labels/{}/comments/{}", issue_id, comment_id);
+        let mut map = Map::new();
+        map.insert("resolved".to_string(), Value::Bool(resolved));
+        self.call_endpoint_json::<_, ()>(
             endpoint.as_str(), Verb::Patch, Value::Object(map)
         )?;
         Ok(())
@@ -768,7 +1326,7 @@ impl IssueAPI {
                                    issue_id: String,
                                    comment_id: String) -> APIResult<()> {
         let endpoint = format!("manual-labels/{}/comments/{}", issue_id, comment_id);
-        self.call_endpoint_json(
+        self.call_endpoint_json::<_, ()>(
             endpoint.as_str(), Verb::Delete, Value::Object(Map::new())
         )?;
         Ok(())
@@ -794,6 +1352,55 @@ impl IssueAPI {
         Ok(())
     }
 
+    /// Like `get_manual_labels`, but returns the label payloads as-is,
+    /// so that they can be interpreted according to any label schema.
+    pub(crate) fn get_manual_labels_raw(&self, issues: Vec<String>) -> APIResult<HashMap<String, Map<String, Value>>> {
+        #[derive(Debug, serde::Deserialize)]
+        struct LabelsResponse {
+            manual_labels: HashMap<String, Map<String, Value>>
+        }
+        let mut map = Map::new();
+        map.insert("issue_ids".to_string(),
+                   Value::Array(issues.into_iter().map(Value::String).collect()));
+        let result = self.call_endpoint_json::<_, LabelsResponse>(
+            "manual-labels", Verb::Get, map
+        )?;
+        Ok(result.manual_labels)
+    }
+
+    pub(crate) fn update_manual_label_raw(&self, issue_id: String, label: Map<String, Value>) -> APIResult<()> {
+        let endpoint = format!("manual-labels/{}", issue_id);
+        self.call_endpoint_json::<_, ()>(endpoint.as_str(), Verb::Post, label)?;
+        Ok(())
+    }
+
+    /// Current label of every annotator, per issue.
+    pub(crate) fn get_annotator_labels(&self, issues: Vec<String>) -> APIResult<AnnotatorLabels> {
+        #[derive(Debug, serde::Deserialize)]
+        struct AnnotatorLabelsResponse {
+            manual_labels: AnnotatorLabels
+        }
+        let mut map = Map::new();
+        map.insert("issue_ids".to_string(),
+                   Value::Array(issues.into_iter().map(Value::String).collect()));
+        let result = self.call_endpoint_json::<_, AnnotatorLabelsResponse>(
+            "manual-labels/annotators", Verb::Get, map
+        )?;
+        Ok(result.manual_labels)
+    }
+
+    pub(crate) fn get_label_history(&self, issue_id: String) -> APIResult<Vec<LabelRecord>> {
+        #[derive(Debug, serde::Deserialize)]
+        struct HistoryResponse {
+            history: Vec<LabelRecord>
+        }
+        let endpoint = format!("manual-labels/{}/history", issue_id);
+        let result = self.call_endpoint_json::<_, HistoryResponse>(
+            endpoint.as_str(), Verb::Get, Value::Object(Map::new())
+        )?;
+        Ok(result.history)
+    }
+
     /***************************************************************************
      * Embedding-related endpoints
      */
@@ -830,7 +1437,7 @@ impl IssueAPI {
         let endpoint = format!("embeddings/{}", id);
         self.call_endpoint_json::<_, UnboundEmbedding>(
             endpoint.as_str(), Verb::Get, Value::Object(Map::new())
-        )
+        ).map_err(|e| e.not_found(|| format!("embedding {id}")))
     }
 
     pub(crate) fn update_embedding(&self,
@@ -991,7 +1598,7 @@ impl IssueAPI {
         let endpoint = format!("models/{}", id);
         self.call_endpoint_json::<_, UnboundModelConfig>(
             endpoint.as_str(), Verb::Get, Value::Object(Map::new())
-        )
+        ).map_err(|e| e.not_found(|| format!("model {id}")))
     }
 
     pub(crate) fn update_model_config(&self,
@@ -1045,31 +1652,77 @@ impl IssueAPI {
         Ok(converted)
     }
 
+    /// Upload a model binary, returning the ID of the new version
+    /// and the SHA-256 checksum of the binary.
     pub(crate) fn upload_model_version(&self,
                                        model_id: String,
-                                       file: String) -> APIResult<String> {
+                                       file: String,
+                                       progress: Option<ProgressCallback>) -> APIResult<(String, String)> {
         #[derive(Debug, serde::Deserialize)]
         struct NewVersionResponse {
             version_id: String
         }
-        let form = multipart::Form::new()
-            .file("file", file)?;
+        let checksum = sha256_file(&file)?;
+        let size = std::fs::metadata(&file)?.len();
+        let file_name = std::path::Path::new(&file)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let reader = ProgressReader::new(std::fs::File::open(&file)?, Some(size), progress);
+        let part = multipart::Part::reader_with_length(reader, size)
+            .file_name(file_name)
+            .mime_str("application/octet-stream")?;
+        let form = multipart::Form::new().part("file", part);
         let endpoint = format!("models/{}/versions", model_id);
         let result = self.call_endpoint_multipart::<NewVersionResponse>(
             endpoint.as_str(), Verb::Post, form
         )?;
-        Ok(result.version_id)
+        Ok((result.version_id, checksum))
+    }
+
+    /// Look up a single version, without downloading the model itself.
+    /// Servers which cannot return the description of a single version
+    /// are handled by looking the version up in the list of all versions.
+    pub(crate) fn get_model_version(&self,
+                                    model_id: String,
+                                    version_id: String) -> APIResult<UnboundModelVersion> {
+        #[derive(Debug, serde::Deserialize)]
+        struct DescriptionResponse {
+            description: String
+        }
+        let endpoint = format!("models/{}/versions/{}/description", model_id, version_id);
+        let result = self.call_endpoint_json::<_, DescriptionResponse>(
+            endpoint.as_str(), Verb::Get, Value::Object(Map::new())
+        );
+        let not_found = || format!("version {version_id} of model {model_id}");
+        match result {
+            Ok(response) => {
+                Ok(UnboundModelVersion{model_id, version_id, description: response.description})
+            },
+            Err(APIError::HTTPError{status_code: 404 | 405 | 501, ..}) => {
+                self.get_versions_for_model(model_id.clone())
+                    .map_err(|e| e.not_found(not_found))?
+                    .into_iter()
+                    .find(|v| v.version_id == version_id)
+                    .ok_or_else(|| APIError::NotFound(not_found()))
+            },
+            Err(e) => Err(e)
+        }
     }
 
     pub(crate) fn download_model_version(&self,
                                          model_id: String,
                                          version_id: String,
-                                         filename: String) -> APIResult<()> {
+                                         filename: String,
+                                         expected_sha256: Option<&str>,
+                                         progress: Option<ProgressCallback>) -> APIResult<()> {
         let endpoint = format!("models/{}/versions/{}", model_id, version_id);
-        self.call_endpoint_download(endpoint.as_str(),
-                                    Verb::Get,
-                                    Value::Object(Map::new()),
-                                    filename)
+        self.call_endpoint_download_resumable(endpoint.as_str(),
+                                              Verb::Get,
+                                              Value::Object(Map::new()),
+                                              filename,
+                                              expected_sha256,
+                                              progress.as_ref())
     }
 
     pub(crate) fn delete_model_version(&self,
@@ -1099,6 +1752,16 @@ impl IssueAPI {
                                   model_id: String,
                                   version_id: String,
                                   issues: Option<Vec<String>>) -> APIResult<HashMap<String, Value>> {
+        self.stream_predictions(model_id, version_id, issues)?.collect()
+    }
+
+    /// Download predictions, parsing them while they are being received.
+    /// The throttle permit is released once the response headers have
+    /// been received; the body is read by the returned stream.
+    pub(crate) fn stream_predictions(&self,
+                                     model_id: String,
+                                     version_id: String,
+                                     issues: Option<Vec<String>>) -> APIResult<PredictionStream> {
         let endpoint = format!("models/{}/versions/{}/predictions", model_id, version_id);
         let mut map = Map::new();
         let payload = match issues {
@@ -1106,30 +1769,61 @@ impl IssueAPI {
             Some(ids) => Value::Array(ids.into_iter().map(Value::String).collect())
         };
         map.insert("issue_ids".to_string(), payload);
-        #[derive(Debug, serde::Deserialize)]
-        struct PredictionsResponse {
-            predictions: HashMap<String, Value>
-        }
-        //let result = self.call_endpoint_json::<_, PredictionsResponse>(
-        //    endpoint.as_str(), Verb::Get, Value::Object(map)
-        //)?;
-        let result = self.call_endpoint_download_object::<_, PredictionsResponse>(
-            endpoint.as_str(), Verb::Get, Value::Object(map)
-        )?;
-        Ok(result.predictions)
+        let request = self.build_request_base(endpoint.as_str(), Verb::Get)?
+            .json(&Value::Object(map))
+            .build()?;
+        let span = tracing::info_span!(
+            "issue_api_download",
+            endpoint = endpoint.as_str(),
+            verb = %Verb::Get,
+            status = tracing::field::Empty,
+            throttle_ms = tracing::field::Empty,
+            retries = 0u32,
+        );
+        let _entered = span.enter();
+        let waiting = Instant::now();
+        let permit = self.throttle.acquire()?;
+        span.record("throttle_ms", waiting.elapsed().as_millis() as u64);
+        let start = Instant::now();
+        let response = self.client.execute(request)
+            .and_then(|r| {
+                span.record("status", r.status().as_u16());
+                r.error_for_status()
+            });
+        drop(permit);
+        let response = match response {
+            Ok(r) => r,
+            Err(e) => {
+                self.metrics.record_request(0, 0, start.elapsed(), false);
+                tracing::warn!(error = %e, "download failed");
+                return Err(e.into());
+            }
+        };
+        let metrics = self.metrics();
+        let stream = PredictionStream::spawn(response, move |bytes_received, succeeded| {
+            metrics.record_request(0, bytes_received, start.elapsed(), succeeded);
+        });
+        Ok(stream)
     }
 
     pub(crate) fn store_predictions(&self,
                                     model_id: String,
                                     version_id: String,
                                     predictions: HashMap<String, Value>) -> APIResult<()> {
+        self.store_prediction_stream(model_id, version_id, predictions.into_iter())
+    }
+
+    /// Upload predictions, serializing them one at a time while they are being sent.
+    pub(crate) fn store_prediction_stream<I>(&self,
+                                             model_id: String,
+                                             version_id: String,
+                                             predictions: I) -> APIResult<()>
+        where
+            I: Iterator<Item=(String, Value)> + Send + 'static
+    {
         let endpoint = format!("models/{}/versions/{}/predictions", model_id, version_id);
-        let mut map = Map::new();
-        map.insert("predictions".to_string(),
-                   Value::Object(Map::from_iter(predictions.into_iter())));
-        //self.call_endpoint_json(endpoint.as_str(), Verb::Post, Value::Object(map))
-        self.call_endpoint_multipart_object(
-            endpoint.as_str(), Verb::Post, &Value::Object(map)
+        self.call_endpoint_multipart_reader(
+            endpoint.as_str(), Verb::Post, PredictionUpload::new(predictions)
         )
     }
 
@@ -1189,6 +1883,36 @@ impl IssueAPI {
         Ok(result.performance_id)
     }
 
+    /// Look up a single test run, without downloading its performance data.
+    /// Like `get_model_version`, this falls back to the list of all
+    /// test runs if the server cannot return a single description.
+    pub(crate) fn get_test_run(&self,
+                               model_id: String,
+                               performance_id: String) -> APIResult<UnboundTestRun> {
+        #[derive(Debug, serde::Deserialize)]
+        struct DescriptionResponse {
+            description: String
+        }
+        let endpoint = format!("models/{}/performances/{}/description", model_id, performance_id);
+        let result = self.call_endpoint_json::<_, DescriptionResponse>(
+            endpoint.as_str(), Verb::Get, Value::Object(Map::new())
+        );
+        let not_found = || format!("test run {performance_id} of model {model_id}");
+        match result {
+            Ok(response) => {
+                Ok(UnboundTestRun{model_id, performance_id, description: response.description})
+            },
+            Err(APIError::HTTPError{status_code: 404 | 405 | 501, ..}) => {
+                self.get_performances_for_model(model_id.clone())
+                    .map_err(|e| e.not_found(not_found))?
+                    .into_iter()
+                    .find(|r| r.performance_id == performance_id)
+                    .ok_or_else(|| APIError::NotFound(not_found()))
+            },
+            Err(e) => Err(e)
+        }
+    }
+
     pub(crate) fn get_performance_data(&self,
                                        model_id: String,
                                        performance_id: String) -> APIResult<Vec<Value>> {
@@ -1264,7 +1988,7 @@ impl IssueAPI {
         let endpoint = format!("files/{}", file_id);
         let response = self.call_endpoint_json::<_, UnboundFile>(
             endpoint.as_str(), Verb::Get, Value::Object(Map::new())
-        )?;
+        ).map_err(|e| e.not_found(|| format!("file {file_id}")))?;
         Ok(response)
     }
     
@@ -0,0 +1,123 @@
+use chrono::{DateTime, FixedOffset};
+use regex::Regex;
+use serde_json::{Map, Value};
+use crate::comments::Comment;
+use crate::errors::{APIError, APIResult};
+
+#[allow(unused)]
+#[derive(Debug, Clone)]
+enum TextMatch {
+    Substring{needle: String, case_sensitive: bool},
+    Pattern(Regex)
+}
+
+/// Criteria for searching labelling comments.
+/// All criteria which are set must match.
+#[allow(unused)]
+#[derive(Debug, Clone, Default)]
+pub struct CommentFilter {
+    author: Option<String>,
+    text: Option<TextMatch>,
+    after: Option<DateTime<FixedOffset>>,
+    before: Option<DateTime<FixedOffset>>
+}
+
+fn parse_timestamp(timestamp: &str) -> APIResult<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map_err(|e| APIError::ValidationError(format!("Invalid timestamp {timestamp}: {e}")))
+}
+
+#[allow(unused)]
+impl CommentFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn author(mut self, author: String) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    pub fn contains(mut self, needle: String, case_sensitive: bool) -> Self {
+        self.text = Some(TextMatch::Substring{needle, case_sensitive});
+        self
+    }
+
+    pub fn matches(mut self, pattern: &str) -> APIResult<Self> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| APIError::ValidationError(format!("Invalid pattern: {e}")))?;
+        self.text = Some(TextMatch::Pattern(regex));
+        Ok(self)
+    }
+
+    /// Only match comments created at or after the given RFC 3339 timestamp.
+    pub fn after(mut self, timestamp: &str) -> APIResult<Self> {
+        self.after = Some(parse_timestamp(timestamp)?);
+        Ok(self)
+    }
+
+    /// Only match comments created before the given RFC 3339 timestamp.
+    pub fn before(mut self, timestamp: &str) -> APIResult<Self> {
+        self.before = Some(parse_timestamp(timestamp)?);
+        Ok(self)
+    }
+
+    // `Option::is_none_or` would need Rust 1.82
+    #[allow(clippy::unnecessary_map_or)]
+    pub fn is_match(&self, comment: &Comment) -> bool {
+        if let Some(ref author) = self.author {
+            if comment.author() != author {
+                return false;
+            }
+        }
+        let text_matches = match self.text {
+            None => true,
+            Some(TextMatch::Substring{ref needle, case_sensitive: true}) => {
+                comment.text().contains(needle.as_str())
+            },
+            Some(TextMatch::Substring{ref needle, case_sensitive: false}) => {
+                comment.text().to_lowercase().contains(&needle.to_lowercase())
+            },
+            Some(TextMatch::Pattern(ref regex)) => regex.is_match(comment.text())
+        };
+        if !text_matches {
+            return false;
+        }
+        if self.after.is_none() && self.before.is_none() {
+            return true;
+        }
+        // Comments without a (valid) timestamp never match a date range
+        match comment.timestamp().map(|t| DateTime::parse_from_rfc3339(t)) {
+            Some(Ok(created)) => {
+                self.after.map_or(true, |a| created >= a) &&
+                    self.before.map_or(true, |b| created < b)
+            },
+            _ => false
+        }
+    }
+
+    /// Payload for the server-side comment search endpoint.
+    pub(crate) fn to_json(&self) -> Map<String, Value> {
+        let mut map = Map::new();
+        if let Some(ref author) = self.author {
+            map.insert("author".to_string(), Value::String(author.clone()));
+        }
+        match self.text {
+            None => {},
+            Some(TextMatch::Substring{ref needle, case_sensitive}) => {
+                map.insert("text".to_string(), Value::String(needle.clone()));
+                map.insert("case_sensitive".to_string(), Value::Bool(case_sensitive));
+            },
+            Some(TextMatch::Pattern(ref regex)) => {
+                map.insert("pattern".to_string(), Value::String(regex.as_str().to_string()));
+            }
+        }
+        if let Some(after) = self.after {
+            map.insert("after".to_string(), Value::String(after.to_rfc3339()));
+        }
+        if let Some(before) = self.before {
+            map.insert("before".to_string(), Value::String(before.to_rfc3339()));
+        }
+        map
+    }
+}
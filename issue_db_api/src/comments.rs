@@ -1,19 +1,77 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use crate::api_core::IssueAPI;
-use crate::errors::APIResult;
+use crate::errors::{APIError, APIResult};
+
+/// A previous version of the text of a comment.
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct CommentEdit {
+    pub text: String,
+    #[serde(default)] pub timestamp: Option<String>
+}
+
+/// A comment as returned by the server.
+#[allow(unused)]
+#[derive(Debug, serde::Deserialize)]
+pub(crate) struct RawComment {
+    author: String,
+    comment: String,
+    #[serde(default)] timestamp: Option<String>,
+    #[serde(default)] edits: Vec<CommentEdit>,
+    #[serde(default)] reply_to: Option<String>,
+    #[serde(default)] resolved: bool
+}
 
 #[allow(unused)]
 #[derive(Debug)]
 pub struct UnboundComment {
     pub(crate) id: String,
     pub(crate) author: String,
-    pub(crate) text: String
+    pub(crate) text: String,
+    pub(crate) timestamp: Option<String>,
+    pub(crate) edits: Vec<CommentEdit>,
+    pub(crate) reply_to: Option<String>,
+    pub(crate) resolved: bool
 }
 
 impl UnboundComment {
     pub fn into_comment(self, api: Arc<IssueAPI>, parent: String) -> Comment {
-        Comment{api, parent, id: self.id, author: self.author, text: self.text}
+        Comment{
+            api,
+            parent,
+            id: self.id,
+            author: self.author,
+            text: self.text,
+            timestamp: self.timestamp,
+            edits: self.edits,
+            reply_to: self.reply_to,
+            resolved: self.resolved
+        }
+    }
+}
+
+/// Convert the comments of a single issue, ordered by creation
+/// (i.e. by their object IDs).
+pub(crate) fn convert_raw_comments(raw: HashMap<String, RawComment>) -> APIResult<Vec<UnboundComment>> {
+    let mut pairs = Vec::with_capacity(raw.len());
+    for (id, c) in raw {
+        let object_id = u128::from_str_radix(id.as_str(), 16)
+            .map_err(|e| APIError::IDParsingError(e.to_string()))?;
+        let comment = UnboundComment{
+            id,
+            author: c.author,
+            text: c.comment,
+            timestamp: c.timestamp,
+            edits: c.edits,
+            reply_to: c.reply_to,
+            resolved: c.resolved
+        };
+        pairs.push((object_id, comment));
     }
+    pairs.sort_by_key(|p| p.0);
+    Ok(pairs.into_iter().map(|(_, c)| c).collect())
 }
 
 
@@ -24,14 +82,24 @@ pub struct Comment {
     parent: String,
     id: String,
     author: String,
-    text: String
+    text: String,
+    timestamp: Option<String>,
+    edits: Vec<CommentEdit>,
+    reply_to: Option<String>,
+    resolved: bool
 }
 
+#[allow(unused)]
 impl Comment {
-    pub(crate) fn identifier(&self) -> &String {
+    pub fn identifier(&self) -> &String {
         &self.id
     }
 
+    /// ID of the issue this comment belongs to.
+    pub fn issue_id(&self) -> &String {
+        &self.parent
+    }
+
     pub fn author(&self) -> &String {
         &self.author
     }
@@ -40,12 +108,131 @@ impl Comment {
         &self.text
     }
 
+    pub fn timestamp(&self) -> Option<&String> {
+        self.timestamp.as_ref()
+    }
+
+    /// Previous versions of the text, oldest first.
+    pub fn edit_history(&self) -> &Vec<CommentEdit> {
+        &self.edits
+    }
+
+    /// ID of the comment this comment is a reply to.
+    pub fn reply_to(&self) -> Option<&String> {
+        self.reply_to.as_ref()
+    }
+
+    pub fn resolved(&self) -> bool {
+        self.resolved
+    }
+
     pub fn update_text(&mut self, text: String) -> APIResult<()> {
         self.api.update_labeling_comment(
             self.parent.clone(),
             self.id.clone(),
             text.clone())?;
-        self.text = text;
+        let previous = std::mem::replace(&mut self.text, text);
+        // The time of the edit is only known to the server
+        self.edits.push(CommentEdit{text: previous, timestamp: None});
+        Ok(())
+    }
+
+    pub fn set_resolved(&mut self, resolved: bool) -> APIResult<()> {
+        self.api.set_labeling_comment_resolved(self.parent.clone(), self.id.clone(), resolved)?;
+        self.resolved = resolved;
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Post a reply to this comment. The reply is fetched back
+    /// from the server, so that its author and timestamp are known.
+    pub fn reply(&self, text: String) -> APIResult<Comment> {
+        let id = self.api.add_labeling_comment_to_issue(
+            self.parent.clone(), text, Some(self.id.clone())
+        )?;
+        let unbound = self.api
+            .get_labeling_comments_for_issue(self.parent.clone())?
+            .into_iter()
+            .find(|c| c.id == id)
+            .ok_or_else(|| APIError::NotFound(format!("comment {id} on issue {}", self.parent)))?;
+        Ok(unbound.into_comment(self.api.clone(), self.parent.clone()))
+    }
+
+    pub fn to_record(&self) -> CommentRecord {
+        CommentRecord{
+            issue_id: self.parent.clone(),
+            id: self.id.clone(),
+            author: self.author.clone(),
+            text: self.text.clone(),
+            timestamp: self.timestamp.clone(),
+            edits: self.edits.clone(),
+            reply_to: self.reply_to.clone(),
+            resolved: self.resolved
+        }
+    }
+}
+
+/// Plain, serializable representation of a comment.
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct CommentRecord {
+    pub issue_id: String,
+    pub id: String,
+    pub author: String,
+    pub text: String,
+    pub timestamp: Option<String>,
+    pub edits: Vec<CommentEdit>,
+    pub reply_to: Option<String>,
+    pub resolved: bool
+}
+
+/// A comment together with all (transitive) replies to it.
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct CommentThread {
+    pub comment: Comment,
+    pub replies: Vec<CommentThread>
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CommentThreadRecord {
+    #[serde(flatten)] pub comment: CommentRecord,
+    pub replies: Vec<CommentThreadRecord>
+}
+
+#[allow(unused)]
+impl CommentThread {
+    /// Arrange the comments of a single issue into threads.
+    /// Replies to comments which no longer exist become threads of their own.
+    pub fn build(comments: Vec<Comment>) -> Vec<CommentThread> {
+        let ids = comments.iter().map(|c| c.id.clone()).collect::<std::collections::HashSet<_>>();
+        let mut children: HashMap<String, Vec<Comment>> = HashMap::new();
+        let mut roots = Vec::new();
+        for comment in comments {
+            match comment.reply_to.clone() {
+                Some(parent) if ids.contains(&parent) => children.entry(parent).or_default().push(comment),
+                _ => roots.push(comment)
+            }
+        }
+        roots.into_iter()
+            .map(|c| Self::attach(c, &mut children))
+            .collect()
+    }
+
+    fn attach(comment: Comment, children: &mut HashMap<String, Vec<Comment>>) -> CommentThread {
+        let replies = children.remove(&comment.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|c| Self::attach(c, children))
+            .collect();
+        CommentThread{comment, replies}
+    }
+
+    pub fn to_record(&self) -> CommentThreadRecord {
+        CommentThreadRecord{
+            comment: self.comment.to_record(),
+            replies: self.replies.iter().map(Self::to_record).collect()
+        }
+    }
+}
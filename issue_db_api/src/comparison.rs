@@ -0,0 +1,395 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use crate::errors::APIResult;
+use crate::models::{ModelVersion, TestRun};
+use crate::performance::{final_records, MetricSummary, PerformanceRecord};
+use crate::query::Query;
+
+const COLUMNS: [&str; 7] = ["metric", "run", "n", "mean", "std", "delta", "p-value"];
+
+/// Per metric, the value of every (fold, split) of a single run.
+type FoldValues<'a> = HashMap<&'a String, BTreeMap<(u32, Option<&'a str>), f64>>;
+
+/// Comparison of a single metric across runs.
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct MetricComparison {
+    pub metric: String,
+    /// Per run, in the order of `RunComparison::runs`;
+    /// `None` if the run does not have the metric.
+    pub summaries: Vec<Option<MetricSummary>>,
+    /// Difference between the mean of every run and that of the first (baseline) run.
+    pub deltas: Vec<Option<f64>>,
+    /// Two-sided p-value of a paired t-test between every run
+    /// and the baseline run, over the folds both runs have.
+    pub p_values: Vec<Option<f64>>
+}
+
+/// Metrics of multiple runs, aligned by metric name.
+/// The first run is used as the baseline for deltas and significance tests.
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct RunComparison {
+    pub runs: Vec<String>,
+    pub metrics: Vec<MetricComparison>
+}
+
+#[allow(unused)]
+impl RunComparison {
+    /// Compare the records of the given runs; see `aggregate_records`
+    /// for how records are selected. `runs` holds the name of every
+    /// entry of `records`.
+    pub(crate) fn from_records(runs: Vec<String>,
+                               records: &[Vec<PerformanceRecord>],
+                               split: Option<&str>) -> Self {
+        // run -> metric -> (fold, split) -> value; values of records without a fold
+        // are included in the summaries, but cannot be paired.
+        debug_assert_eq!(runs.len(), records.len());
+        let mut per_fold: Vec<FoldValues> = Vec::new();
+        let mut all_values: Vec<HashMap<&String, Vec<f64>>> = Vec::new();
+        let mut names = BTreeSet::new();
+        for run in records {
+            let mut folds: FoldValues = HashMap::new();
+            let mut values: HashMap<&String, Vec<f64>> = HashMap::new();
+            for record in final_records(run, split) {
+                for (metric, value) in record.metrics.iter() {
+                    names.insert(metric);
+                    values.entry(metric).or_default().push(*value);
+                    if let Some(fold) = record.fold {
+                        let key = (fold, record.split.as_deref());
+                        folds.entry(metric).or_default().insert(key, *value);
+                    }
+                }
+            }
+            per_fold.push(folds);
+            all_values.push(values);
+        }
+        let metrics = names.into_iter()
+            .map(|metric| {
+                let summaries = all_values.iter()
+                    .map(|v| v.get(metric).map(|values| MetricSummary::from_values(values)))
+                    .collect::<Vec<_>>();
+                let baseline = summaries.first().copied().flatten();
+                let deltas = summaries.iter()
+                    .map(|s| Some(s.as_ref()?.mean - baseline.as_ref()?.mean))
+                    .collect();
+                let baseline_folds = per_fold.first().and_then(|f| f.get(metric));
+                let p_values = per_fold.iter()
+                    .map(|folds| {
+                        let (baseline, other) = (baseline_folds?, folds.get(metric)?);
+                        let (a, b): (Vec<f64>, Vec<f64>) = baseline.iter()
+                            .filter_map(|(fold, x)| Some((*x, *other.get(fold)?)))
+                            .unzip();
+                        paired_t_test(&a, &b)
+                    })
+                    .collect();
+                MetricComparison{metric: metric.clone(), summaries, deltas, p_values}
+            })
+            .collect();
+        Self{runs, metrics}
+    }
+
+    fn rows(&self) -> Vec<[String; 7]> {
+        let mut rows = Vec::new();
+        for metric in self.metrics.iter() {
+            for (i, run) in self.runs.iter().enumerate() {
+                // The fields are public, so they need not be consistent
+                let Some(summary) = metric.summaries.get(i).copied().flatten() else { continue; };
+                rows.push([
+                    metric.metric.clone(),
+                    run.clone(),
+                    summary.count.to_string(),
+                    format!("{:.4}", summary.mean),
+                    summary.std.map(|s| format!("{s:.4}")).unwrap_or_default(),
+                    // The baseline is compared to itself
+                    if i == 0 { String::new() } else {
+                        metric.deltas.get(i).copied().flatten().map(|d| format!("{d:+.4}")).unwrap_or_default()
+                    },
+                    if i == 0 { String::new() } else {
+                        metric.p_values.get(i).copied().flatten().map(|p| format!("{p:.4}")).unwrap_or_default()
+                    }
+                ]);
+            }
+        }
+        rows
+    }
+
+    /// Plain text table with aligned columns.
+    pub fn to_table(&self) -> String {
+        let rows = self.rows();
+        let mut widths = COLUMNS.map(str::len);
+        for row in rows.iter() {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let format_row = |cells: &[String]| {
+            cells.iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+        let header = COLUMNS.map(String::from);
+        let separator = widths.map(|w| "-".repeat(w));
+        std::iter::once(format_row(&header))
+            .chain(std::iter::once(format_row(&separator)))
+            .chain(rows.iter().map(|r| format_row(r)))
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let escape = |cell: &String| {
+            if cell.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.clone()
+            }
+        };
+        let header = COLUMNS.map(String::from);
+        std::iter::once(&header)
+            .chain(self.rows().iter())
+            .map(|row| row.iter().map(escape).collect::<Vec<_>>().join(",") + "\n")
+            .collect()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let format_row = |cells: &[String]| {
+            let cells = cells.iter()
+                .map(|c| c.replace('|', "\\|"))
+                .collect::<Vec<_>>();
+            format!("| {} |\n", cells.join(" | "))
+        };
+        let header = COLUMNS.map(String::from);
+        let separator = COLUMNS.map(|_| "---".to_string());
+        std::iter::once(format_row(&header))
+            .chain(std::iter::once(format_row(&separator)))
+            .chain(self.rows().iter().map(|r| format_row(r)))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for RunComparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_table())
+    }
+}
+
+fn display_name(description: String, id: String) -> String {
+    if description.is_empty() { id } else { description }
+}
+
+/// Compare test runs across folds. The first run is the baseline.
+pub fn compare_runs(runs: &[TestRun], split: Option<&str>) -> APIResult<RunComparison> {
+    let mut names = Vec::with_capacity(runs.len());
+    let mut records = Vec::with_capacity(runs.len());
+    for run in runs {
+        names.push(display_name(run.description(), run.run_id()));
+        records.push(run.records()?);
+    }
+    Ok(RunComparison::from_records(names, &records, split))
+}
+
+/// Evaluate every version on the same issues, and compare the results.
+/// The first version is the baseline. Since every evaluation
+/// is a single record, there are no p-values.
+pub fn compare_versions(versions: &[ModelVersion],
+                        query: Query,
+                        threshold: f64) -> APIResult<RunComparison> {
+    let mut names = Vec::with_capacity(versions.len());
+    let mut records = Vec::with_capacity(versions.len());
+    for version in versions {
+        names.push(display_name(version.description(), version.version_id()));
+        let report = version.evaluate(query.clone(), threshold)?;
+        records.push(vec![report.to_performance_record()]);
+    }
+    Ok(RunComparison::from_records(names, &records, None))
+}
+
+/// Two-sided p-value of a paired t-test. `None` if there are fewer than
+/// two pairs, or if all differences are equal but not zero (the test is
+/// undefined). If all differences are zero, the p-value is 1.
+pub fn paired_t_test(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.len() != b.len() || a.len() < 2 {
+        return None;
+    }
+    let differences = a.iter().zip(b).map(|(x, y)| y - x).collect::<Vec<_>>();
+    if differences.iter().all(|d| *d == 0.0) {
+        return Some(1.0);
+    }
+    let n = differences.len() as f64;
+    let mean = differences.iter().sum::<f64>() / n;
+    let variance = differences.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    if variance <= 0.0 {
+        return None;
+    }
+    let t = mean / (variance / n).sqrt();
+    let df = n - 1.0;
+    Some(regularized_incomplete_beta(df / 2.0, 0.5, df / (df + t * t)))
+}
+
+fn ln_gamma(x: f64) -> f64 {
+    // Lanczos approximation (g = 7, n = 9)
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7
+    ];
+    if x < 0.5 {
+        // Reflection formula
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let mut sum = COEFFICIENTS[0];
+    for (i, c) in COEFFICIENTS.iter().enumerate().skip(1) {
+        sum += c / (x + i as f64);
+    }
+    let t = x + 7.5;
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+fn regularized_incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    // The continued fraction converges quickly only for x < (a + 1) / (a + b + 2)
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 300;
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+    let clamp = |v: f64| if v.abs() < TINY { TINY } else { v };
+    let mut c = 1.0;
+    let mut d = 1.0 / clamp(1.0 - (a + b) * x / (a + 1.0));
+    let mut h = d;
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / clamp(1.0 + even * d);
+        c = clamp(1.0 + even / c);
+        h *= d * c;
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / clamp(1.0 + odd * d);
+        c = clamp(1.0 + odd / c);
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    h
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() < tolerance, "expected {expected}, got {actual}");
+    }
+
+    fn record(fold: u32, split: &str, accuracy: f64) -> PerformanceRecord {
+        PerformanceRecord{
+            fold: Some(fold),
+            split: Some(split.to_string()),
+            ..PerformanceRecord::new(BTreeMap::from([("accuracy".to_string(), accuracy)]))
+        }
+    }
+
+    #[test]
+    fn ln_gamma_known_values() {
+        assert_close(ln_gamma(1.0), 0.0, 1e-12);
+        assert_close(ln_gamma(5.0), 24.0_f64.ln(), 1e-12);
+        assert_close(ln_gamma(0.5), std::f64::consts::PI.sqrt().ln(), 1e-12);
+        assert_close(ln_gamma(0.25), 3.625_609_908_221_908_f64.ln(), 1e-12);
+    }
+
+    #[test]
+    fn regularized_incomplete_beta_known_values() {
+        assert_eq!(regularized_incomplete_beta(2.0, 3.0, 0.0), 0.0);
+        assert_eq!(regularized_incomplete_beta(2.0, 3.0, 1.0), 1.0);
+        // I_x(1, 1) = x
+        assert_close(regularized_incomplete_beta(1.0, 1.0, 0.3), 0.3, 1e-12);
+        // Symmetric around 0.5 for a = b
+        assert_close(regularized_incomplete_beta(4.0, 4.0, 0.5), 0.5, 1e-12);
+    }
+
+    #[test]
+    fn paired_t_test_known_p_values() {
+        // t = 2.0, df = 5
+        let a = [0.0; 6];
+        let b = [0.0, 0.0, 0.0, 1.0, 1.0, 2.0];
+        assert_close(paired_t_test(&a, &b).unwrap(), 0.1019, 1e-4);
+        assert_close(paired_t_test(&b, &a).unwrap(), 0.1019, 1e-4);
+        // t = -1.0, df = 2
+        let a = [1.0, 2.0, 3.0];
+        let b = [1.0, 1.0, 3.0];
+        assert_close(paired_t_test(&a, &b).unwrap(), 0.4226, 1e-4);
+    }
+
+    #[test]
+    fn paired_t_test_degenerate() {
+        assert_eq!(paired_t_test(&[1.0, 2.0, 3.0], &[1.0, 2.0, 3.0]), Some(1.0));
+        assert_eq!(paired_t_test(&[1.0, 2.0, 3.0], &[2.0, 3.0, 4.0]), None);
+        assert_eq!(paired_t_test(&[1.0], &[2.0]), None);
+        assert_eq!(paired_t_test(&[1.0, 2.0], &[1.0]), None);
+    }
+
+    #[test]
+    fn from_records_pairs_folds_per_split() {
+        let baseline = vec![
+            record(0, "train", 0.9), record(1, "train", 0.9),
+            record(0, "test", 0.5), record(1, "test", 0.6), record(2, "test", 0.7)
+        ];
+        let other = vec![
+            record(0, "train", 0.9), record(1, "train", 0.9),
+            record(0, "test", 0.5), record(1, "test", 0.6), record(2, "test", 0.7)
+        ];
+        let comparison = RunComparison::from_records(
+            vec!["a".to_string(), "b".to_string()], &[baseline, other], None
+        );
+        let accuracy = &comparison.metrics[0];
+        assert_eq!(accuracy.summaries[0].unwrap().count, 5);
+        assert_close(accuracy.deltas[1].unwrap(), 0.0, 1e-12);
+        assert_eq!(accuracy.p_values[1], Some(1.0));
+    }
+
+    #[test]
+    fn csv_and_markdown_escaping() {
+        let records = vec![vec![record(0, "test", 0.5)], vec![record(0, "test", 0.6)]];
+        let comparison = RunComparison::from_records(
+            vec!["base, \"v1\"".to_string(), "a | b".to_string()], &records, None
+        );
+        let csv = comparison.to_csv();
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "metric,run,n,mean,std,delta,p-value");
+        assert_eq!(lines[1], "accuracy,\"base, \"\"v1\"\"\",1,0.5000,,,");
+        assert_eq!(lines[2], "accuracy,a | b,1,0.6000,,+0.1000,");
+        let markdown = comparison.to_markdown();
+        let lines = markdown.lines().collect::<Vec<_>>();
+        assert_eq!(lines[1], "| --- | --- | --- | --- | --- | --- | --- |");
+        assert_eq!(lines[3], "| accuracy | a \\| b | 1 | 0.6000 |  | +0.1000 |  |");
+    }
+}
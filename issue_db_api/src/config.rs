@@ -5,6 +5,7 @@ use crate::api_core::IssueAPI;
 use crate::{Query, QueryCMP};
 use crate::errors::{APIError, APIResult};
 use crate::issues::Issue;
+use crate::prefetch::LoadingGroup;
 
 #[allow(unused)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -23,6 +24,15 @@ pub enum CachingPolicy {
 }
 
 
+#[allow(unused)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum PrefetchPolicy {
+    #[default]
+    NoPrefetching,
+    PrefetchSiblings
+}
+
+
 #[allow(unused)]
 #[derive(Debug, Copy, Clone)]
 pub enum IssueAttribute {
@@ -104,6 +114,7 @@ impl TryFrom<String> for IssueAttribute {
 pub struct IssueLoadingSettings {
     attributes: Vec<IssueAttribute>,
     preload_labels: bool,
+    prefetch_policy: Option<PrefetchPolicy>
 }
 
 
@@ -111,13 +122,33 @@ pub struct IssueLoadingSettings {
 impl IssueLoadingSettings {
     pub fn new(attributes: Vec<IssueAttribute>,
                preload_labels: bool) -> Self {
-        Self{attributes, preload_labels}
+        Self{attributes, preload_labels, prefetch_policy: None}
+    }
+
+    /// The same settings, but with the given prefetch policy
+    /// for the loaded issues. Without an explicit policy,
+    /// the policy of the repository is used (or no prefetching,
+    /// when loading issues directly through `load_issues`).
+    pub fn with_prefetch_policy(self, prefetch_policy: PrefetchPolicy) -> Self {
+        Self{prefetch_policy: Some(prefetch_policy), ..self}
+    }
+
+    /// Use `prefetch_policy` unless a policy was set explicitly.
+    pub(crate) fn with_default_prefetch_policy(self, prefetch_policy: PrefetchPolicy) -> Self {
+        let prefetch_policy = self.prefetch_policy.or(Some(prefetch_policy));
+        Self{prefetch_policy, ..self}
+    }
+
+    /// The same settings, but always loading manual labels.
+    pub(crate) fn with_preloaded_labels(self) -> Self {
+        Self{preload_labels: true, ..self}
     }
 
     pub fn load_issues(self,
                        api: Arc<IssueAPI>,
                        ids: Vec<String>,
                        label_caching_policy: CachingPolicy) -> APIResult<Vec<Issue>> {
+        let prefetch_policy = self.prefetch_policy.unwrap_or_default();
         let labels = if self.preload_labels {
             let query = Query::And(vec![
                 Query::Tag(QueryCMP::Eq, "has-label".to_string()),
@@ -131,13 +162,24 @@ impl IssueLoadingSettings {
         let data = api.get_issue_data(
             ids.clone(), self.attributes
         )?;
+        let group = match prefetch_policy {
+            PrefetchPolicy::NoPrefetching => None,
+            PrefetchPolicy::PrefetchSiblings => Some(LoadingGroup::new())
+        };
         let issues = ids.into_iter()
-            .map(|id| Issue::new(
-                api.clone(),
-                id.clone(),
-                data.get(&id).unwrap().clone(),
-                label_caching_policy,
-                if self.preload_labels { Some(labels.get(&id).cloned()) } else { None }))
+            .map(|id| {
+                let issue_data = Arc::new(data.get(&id).unwrap().clone());
+                if let Some(ref g) = group {
+                    g.register(&issue_data);
+                }
+                Issue::new(
+                    api.clone(),
+                    id.clone(),
+                    issue_data,
+                    label_caching_policy,
+                    prefetch_policy,
+                    if self.preload_labels { Some(labels.get(&id).cloned()) } else { None })
+            })
             .collect();
         Ok(issues)
     }
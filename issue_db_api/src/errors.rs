@@ -7,6 +7,9 @@ pub enum APIError {
     HTTPError{message: String, status_code: u16},
     IDParsingError(String),
     LibraryError(String),
+    ValidationError(String),
+    NotFound(String),
+    ChecksumMismatch{expected: String, actual: String},
     GenericError(String)
 }
 
@@ -21,6 +24,11 @@ impl std::fmt::Display for APIError {
             },
             APIError::IDParsingError(msg) => write!(f, "Error while parsing ObjectID: {msg}"),
             APIError::LibraryError(msg) => write!(f, "Internal error in library: {msg}"),
+            APIError::ValidationError(msg) => write!(f, "Validation error: {msg}"),
+            APIError::NotFound(resource) => write!(f, "Not found: {resource}"),
+            APIError::ChecksumMismatch{expected, actual} => {
+                write!(f, "Checksum mismatch: expected SHA-256 {expected}, got {actual}")
+            },
             APIError::GenericError(msg) => write!(f, "Error: {msg}")
         }
     }
@@ -28,6 +36,16 @@ impl std::fmt::Display for APIError {
 
 impl std::error::Error for APIError {}
 
+impl APIError {
+    /// Turn a 404 response into a `NotFound` error for the given resource.
+    pub(crate) fn not_found<F: FnOnce() -> String>(self, resource: F) -> Self {
+        match self {
+            APIError::HTTPError{status_code: 404, ..} => APIError::NotFound(resource()),
+            other => other
+        }
+    }
+}
+
 impl From<reqwest::Error> for APIError {
     fn from(value: reqwest::Error) -> Self {
         if value.is_status() {
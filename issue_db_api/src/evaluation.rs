@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use serde_json::Value;
+use crate::errors::{APIError, APIResult};
+use crate::labels::Label;
+use crate::performance::PerformanceRecord;
+use crate::predictions::{ClassPrediction, Prediction};
+
+/// Binary confusion matrix for a single class.
+#[allow(unused)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ConfusionMatrix {
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub true_negatives: usize,
+    pub false_negatives: usize
+}
+
+fn ratio(numerator: usize, denominator: usize) -> Option<f64> {
+    if denominator == 0 {
+        None
+    } else {
+        Some(numerator as f64 / denominator as f64)
+    }
+}
+
+#[allow(unused)]
+impl ConfusionMatrix {
+    pub fn record(&mut self, predicted: bool, actual: bool) {
+        match (predicted, actual) {
+            (true, true) => self.true_positives += 1,
+            (true, false) => self.false_positives += 1,
+            (false, false) => self.true_negatives += 1,
+            (false, true) => self.false_negatives += 1
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.true_positives + self.false_positives + self.true_negatives + self.false_negatives
+    }
+
+    /// Number of issues which actually belong to the class.
+    pub fn support(&self) -> usize {
+        self.true_positives + self.false_negatives
+    }
+
+    /// `None` if nothing was predicted to belong to the class.
+    pub fn precision(&self) -> Option<f64> {
+        ratio(self.true_positives, self.true_positives + self.false_positives)
+    }
+
+    /// `None` if nothing actually belongs to the class.
+    pub fn recall(&self) -> Option<f64> {
+        ratio(self.true_positives, self.support())
+    }
+
+    /// `None` if the class was neither predicted nor actually present.
+    pub fn f1(&self) -> Option<f64> {
+        ratio(2 * self.true_positives,
+              2 * self.true_positives + self.false_positives + self.false_negatives)
+    }
+
+    pub fn accuracy(&self) -> Option<f64> {
+        ratio(self.true_positives + self.true_negatives, self.total())
+    }
+}
+
+/// Confusion matrix and derived metrics for a single class.
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ClassMetrics {
+    pub class: String,
+    pub confusion_matrix: ConfusionMatrix,
+    pub precision: Option<f64>,
+    pub recall: Option<f64>,
+    pub f1: Option<f64>,
+    pub accuracy: Option<f64>,
+    pub support: usize
+}
+
+impl ClassMetrics {
+    fn new(class: &str, confusion_matrix: ConfusionMatrix) -> Self {
+        Self{
+            class: class.to_string(),
+            precision: confusion_matrix.precision(),
+            recall: confusion_matrix.recall(),
+            f1: confusion_matrix.f1(),
+            accuracy: confusion_matrix.accuracy(),
+            support: confusion_matrix.support(),
+            confusion_matrix
+        }
+    }
+}
+
+/// Result of evaluating the predictions of a model version
+/// against the manual labels of a set of issues.
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct EvaluationReport {
+    pub model_id: String,
+    pub version_id: String,
+    pub threshold: f64,
+    /// Number of labelled issues with a prediction.
+    pub issues: usize,
+    /// Number of labelled issues without a prediction; these are not evaluated.
+    pub missing_predictions: usize,
+    /// Number of issues for which all classes were predicted correctly.
+    pub exact_matches: usize,
+    /// Metrics for `existence`, `executive`, `property` and `non-architectural`, in that order.
+    pub classes: Vec<ClassMetrics>
+}
+
+#[allow(unused)]
+impl EvaluationReport {
+    pub fn class(&self, name: &str) -> Option<&ClassMetrics> {
+        self.classes.iter().find(|c| c.class == name)
+    }
+
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).expect("EvaluationReport is always serializable")
+    }
+
+    /// Record with the (defined) metrics of every class as `<class>_<metric>`,
+    /// and the full report under `evaluation`.
+    pub fn to_performance_record(&self) -> PerformanceRecord {
+        let mut record = PerformanceRecord{
+            split: Some("evaluation".to_string()),
+            ..PerformanceRecord::default()
+        };
+        for class in self.classes.iter() {
+            let metrics = [
+                ("precision", class.precision),
+                ("recall", class.recall),
+                ("f1", class.f1),
+                ("accuracy", class.accuracy)
+            ];
+            for (name, value) in metrics {
+                if let Some(v) = value {
+                    record.metrics.insert(format!("{}_{name}", class.class), v);
+                }
+            }
+        }
+        if self.issues > 0 {
+            let ratio = self.exact_matches as f64 / self.issues as f64;
+            record.metrics.insert("exact_match_ratio".to_string(), ratio);
+        }
+        record.extra.insert("evaluation".to_string(), self.to_json());
+        record
+    }
+}
+
+fn apply_threshold(prediction: &ClassPrediction, threshold: f64) -> bool {
+    match prediction.probability {
+        Some(p) => p >= threshold,
+        None => prediction.predicted
+    }
+}
+
+/// Compare predictions to labels. Classes with a predicted probability
+/// are considered positive if the probability is at least `threshold`;
+/// otherwise, the predicted label is used as-is.
+pub(crate) fn evaluate_predictions(model_id: String,
+                                   version_id: String,
+                                   threshold: f64,
+                                   predictions: &HashMap<String, Prediction>,
+                                   labels: &HashMap<String, Label>) -> APIResult<EvaluationReport> {
+    if !(0.0..=1.0).contains(&threshold) {
+        let msg = format!("Threshold must be between 0 and 1, got {threshold}");
+        return Err(APIError::ValidationError(msg));
+    }
+    let mut matrices = [ConfusionMatrix::default(); 4];
+    let mut issues = 0;
+    let mut exact_matches = 0;
+    for (issue, label) in labels {
+        let Some(prediction) = predictions.get(issue) else { continue; };
+        issues += 1;
+        let predicted = Label::new(
+            apply_threshold(&prediction.existence, threshold),
+            apply_threshold(&prediction.executive, threshold),
+            apply_threshold(&prediction.property, threshold)
+        );
+        let pairs = [
+            (predicted.existence(), label.existence()),
+            (predicted.executive(), label.executive()),
+            (predicted.property(), label.property()),
+            (
+                !(predicted.existence() || predicted.executive() || predicted.property()),
+                !(label.existence() || label.executive() || label.property())
+            )
+        ];
+        for (matrix, (p, a)) in matrices.iter_mut().zip(pairs) {
+            matrix.record(p, a);
+        }
+        if predicted == *label {
+            exact_matches += 1;
+        }
+    }
+    let classes = ["existence", "executive", "property", "non-architectural"]
+        .into_iter()
+        .zip(matrices)
+        .map(|(name, matrix)| ClassMetrics::new(name, matrix))
+        .collect();
+    Ok(EvaluationReport{
+        model_id,
+        version_id,
+        threshold,
+        issues,
+        missing_predictions: labels.len() - issues,
+        exact_matches,
+        classes
+    })
+}
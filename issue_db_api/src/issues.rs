@@ -1,20 +1,41 @@
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use crate::api_core::{IssueAPI, IssueData};
-use crate::comments::Comment;
-use crate::config::{CachingPolicy, IssueLoadingSettings};
+use serde_json::{Map, Value};
+use crate::agreement::LabelRecord;
+use crate::api_core::{IssueAPI, IssueData, IssueFields};
+use crate::comments::{Comment, CommentThread};
+use crate::config::{CachingPolicy, IssueLoadingSettings, PrefetchPolicy};
 use crate::errors::APIResult;
 use crate::labels::Label;
+use crate::review::ReviewOutcome;
+use crate::label_schemas::{LabelSchema, ARCHITECTURAL_SCHEMA};
+use crate::schemas::raw_issue_response::deserialize_some;
+use crate::sessions::{find_or_connect, Session};
 use crate::util::CacheContainer;
 
+/// Serializable representation of an issue, containing all
+/// data which was loaded for it. Used to move issues between processes.
+#[allow(unused)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct IssueSnapshot {
+    pub id: String,
+    pub url: String,
+    #[serde(default)] pub allow_self_signed_certificates: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "deserialize_some")]
+    pub manual_label: Option<Option<Label>>,
+    #[serde(default)] pub data: IssueFields
+}
+
 #[allow(unused)]
 #[derive(Debug)]
 pub struct Issue {
     api: Arc<IssueAPI>,
     ident: String,
-    data: IssueData,
+    data: Arc<IssueData>,
     caching_policy: CachingPolicy,
+    prefetch_policy: PrefetchPolicy,
     label: CacheContainer<Option<Label>>,
     dirty: AtomicBool
 }
@@ -37,13 +58,15 @@ impl Eq for Issue {}
 impl Issue {
     pub(crate) fn new(api: Arc<IssueAPI>,
                       ident: String,
-                      data: IssueData,
+                      data: Arc<IssueData>,
                       caching: CachingPolicy,
+                      prefetch: PrefetchPolicy,
                       label: Option<Option<Label>>) -> Self {
         Self{
+            label: CacheContainer::new(label, api.metrics()),
             api, ident, data,
             caching_policy: caching,
-            label: CacheContainer::new(label),
+            prefetch_policy: prefetch,
             dirty: AtomicBool::new(false)
         }
     }
@@ -53,6 +76,63 @@ impl Issue {
         &self.ident
     }
 
+    pub(crate) fn data(&self) -> &IssueData {
+        &self.data
+    }
+
+    /// A second handle to this issue, sharing its loaded data.
+    pub(crate) fn share(&self) -> APIResult<Self> {
+        let issue = Self::new(
+            self.api.clone(),
+            self.ident.clone(),
+            self.data.clone(),
+            self.caching_policy,
+            self.prefetch_policy,
+            self.label.peek()?
+        );
+        Ok(issue)
+    }
+
+    /// All issue attributes which have been loaded so far.
+    pub fn loaded_data(&self) -> APIResult<IssueFields> {
+        self.data.fields()
+    }
+
+    /// Capture the identifier, the cached manual label (if any),
+    /// and all loaded attributes of this issue.
+    pub fn snapshot(&self) -> APIResult<IssueSnapshot> {
+        let snapshot = IssueSnapshot{
+            id: self.ident.clone(),
+            url: self.api.url().clone(),
+            allow_self_signed_certificates: self.api.allows_self_signed_certificates(),
+            manual_label: self.label.peek()?,
+            data: self.data.fields()?
+        };
+        Ok(snapshot)
+    }
+
+    pub(crate) fn from_snapshot(session: Session, snapshot: IssueSnapshot) -> Self {
+        let data = IssueData::from_fields(snapshot.id.clone(), snapshot.data);
+        Self::new(
+            session.api,
+            snapshot.id,
+            Arc::new(data),
+            session.label_caching,
+            session.prefetching,
+            snapshot.manual_label
+        )
+    }
+
+    /// Restore an issue from a snapshot, binding it to the repository
+    /// for the snapshot's server which was created most recently
+    /// in this process. If no such repository exists,
+    /// an unauthenticated, read-only connection is made (and a
+    /// warning is logged); writing through the issue then fails.
+    pub fn restore(snapshot: IssueSnapshot) -> APIResult<Self> {
+        let session = find_or_connect(&snapshot.url, snapshot.allow_self_signed_certificates)?;
+        Ok(Self::from_snapshot(session, snapshot))
+    }
+
     pub fn get_manual_label(&self) -> APIResult<Option<Label>> {
         self.label.get(|| self.load_label())
     }
@@ -68,8 +148,71 @@ impl Issue {
         self.label.set(Some(label))
     }
 
+    /// Get the manual label of this issue according to the given label schema.
+    /// Returns `None` if the issue has no label for the schema.
+    pub fn get_label(&self, schema: &str) -> APIResult<Option<Map<String, Value>>> {
+        let schema = self.api.label_schemas().get(schema)?;
+        let mut labels = self.api.get_manual_labels_raw(vec![self.ident.clone()])?;
+        match labels.remove(&self.ident) {
+            Some(payload) => schema.extract(&payload),
+            None => Ok(None)
+        }
+    }
+
+    /// Set the manual label of this issue for the given label schema.
+    /// The label is validated against the schema before it is sent.
+    /// Labels of other schemas are kept.
+    pub fn set_label(&self, schema: &str, label: Map<String, Value>) -> APIResult<()> {
+        let current = self.api
+            .get_manual_labels_raw(vec![self.ident.clone()])?
+            .remove(&self.ident)
+            .unwrap_or_default();
+        self.merge_labels(current, vec![(schema.to_string(), label)])
+    }
+
+    /// Merge the labels for the given schemas into `payload` (the
+    /// current label payload of this issue), and store the result.
+    /// The server replaces the whole payload, so `payload` must
+    /// contain the labels of all schemas which should be kept.
+    pub(crate) fn merge_labels(&self,
+                               mut payload: Map<String, Value>,
+                               labels: Vec<(String, Map<String, Value>)>) -> APIResult<()> {
+        for (schema, label) in labels {
+            self.api.label_schemas().get(&schema)?.merge_into(&mut payload, label)?;
+        }
+        let architectural = LabelSchema::architectural()
+            .extract(&payload)?
+            .map(|label| Label::try_from(&label))
+            .transpose()?;
+        self.api.update_manual_label_raw(self.ident.clone(), payload)?;
+        self.label.set(architectural)?;
+        Ok(())
+    }
+
+    /// The current label of every annotator of this issue, for the given schema.
+    pub fn get_annotator_labels(&self, schema: &str) -> APIResult<HashMap<String, Map<String, Value>>> {
+        let schema = self.api.label_schemas().get(schema)?;
+        let by_author = self.api
+            .get_annotator_labels(vec![self.ident.clone()])?
+            .remove(&self.ident)
+            .unwrap_or_default();
+        let mut labels = HashMap::new();
+        for (author, payload) in by_author {
+            if let Some(label) = schema.extract(&payload)? {
+                labels.insert(author, label);
+            }
+        }
+        Ok(labels)
+    }
+
+    /// All labels ever set for this issue, oldest first.
+    pub fn label_history(&self) -> APIResult<Vec<LabelRecord>> {
+        self.api.get_label_history(self.ident.clone())
+    }
+
     pub fn invalidate_cached_label(&self) {
         self.dirty.store(true, Ordering::Release);
+        self.label.invalidate();
     }
 
     pub fn get_tags(&self) -> APIResult<Vec<String>> {
@@ -84,12 +227,23 @@ impl Issue {
         self.api.remove_tag_from_issue(self.ident.clone(), name)
     }
 
-    pub fn start_review(&self) -> APIResult<()> {
-        self.api.start_issue_review(self.ident.clone())
+    pub fn start_review(&self, reason: Option<String>) -> APIResult<()> {
+        self.api.start_issue_review(self.ident.clone(), reason)
     }
 
-    pub fn finish_review(&self) -> APIResult<()> {
-        self.api.finish_issue_review(self.ident.clone())
+    pub fn finish_review(&self, outcome: ReviewOutcome) -> APIResult<()> {
+        let architectural = match outcome {
+            ReviewOutcome::Changed{ref schema, ref label} if schema == ARCHITECTURAL_SCHEMA => {
+                Some(Label::try_from(label)?)
+            },
+            _ => None
+        };
+        let payload = outcome.into_payload(&self.api)?;
+        self.api.finish_issue_review(self.ident.clone(), payload)?;
+        if let Some(label) = architectural {
+            self.label.set(Some(label))?;
+        }
+        Ok(())
     }
 
     pub fn in_review(&self) -> APIResult<bool> {
@@ -105,8 +259,12 @@ impl Issue {
         Ok(comments)
     }
 
+    pub fn get_labelling_comment_threads(&self) -> APIResult<Vec<CommentThread>> {
+        Ok(CommentThread::build(self.get_labelling_comments()?))
+    }
+
     pub fn add_labelling_comment(&self, text: String) -> APIResult<()> {
-        let _ = self.api.add_labeling_comment_to_issue(self.ident.clone(), text)?;
+        let _ = self.api.add_labeling_comment_to_issue(self.ident.clone(), text, None)?;
         Ok(())
     }
 
@@ -115,51 +273,51 @@ impl Issue {
     }
 
     #[inline(always)]
-    pub fn key(&self) -> APIResult<&String> {
+    pub fn key(&self) -> APIResult<String> {
         self.data.key(&self.api)
     }
 
     #[inline(always)]
-    pub fn summary(&self) -> APIResult<&String> {
+    pub fn summary(&self) -> APIResult<String> {
         self.data.summary(&self.api)
     }
 
     #[inline(always)]
-    pub fn description(&self) -> APIResult<&String> {
+    pub fn description(&self) -> APIResult<String> {
         self.data.description(&self.api)
     }
 
     #[inline(always)]
-    pub fn comments(&self) -> APIResult<&Vec<String>> {
+    pub fn comments(&self) -> APIResult<Vec<String>> {
         self.data.comments(&self.api)
     }
 
     #[inline(always)]
-    pub fn status(&self) -> APIResult<&String> {
+    pub fn status(&self) -> APIResult<String> {
         self.data.status(&self.api)
     }
 
     #[inline(always)]
-    pub fn priority(&self) -> APIResult<&String> {
+    pub fn priority(&self) -> APIResult<String> {
         self.data.priority(&self.api)
     }
 
     #[inline(always)]
-    pub fn resolution(&self) -> APIResult<&Option<String>> {
+    pub fn resolution(&self) -> APIResult<Option<String>> {
         self.data.resolution(&self.api)
     }
 
     #[inline(always)]
-    pub fn issue_type(&self) -> APIResult<&String> {
+    pub fn issue_type(&self) -> APIResult<String> {
         self.data.issue_type(&self.api)
     }
 
     #[inline(always)]
     pub fn issue_links(&self, loading_settings: IssueLoadingSettings) -> APIResult<Vec<Issue>> {
         let ids = self.data.issue_links(&self.api)?;
-        let issues = loading_settings.load_issues(
-            self.api.clone(), ids.clone(), self.caching_policy
-        )?;
+        let issues = loading_settings
+            .with_default_prefetch_policy(self.prefetch_policy)
+            .load_issues(self.api.clone(), ids.clone(), self.caching_policy)?;
         assert_eq!(issues.len(), ids.len(), "Failed to retrieve all linked issues");
         Ok(issues)
     }
@@ -169,9 +327,9 @@ impl Issue {
         if let Some(id) = self.data.parent(&self.api)? {
             Ok(
                 Some(
-                    loading_settings.load_issue(self.api.clone(),
-                                                id.clone(),
-                                                self.caching_policy)?
+                    loading_settings
+                        .with_default_prefetch_policy(self.prefetch_policy)
+                        .load_issue(self.api.clone(), id, self.caching_policy)?
                 )
             )
         } else {
@@ -182,9 +340,9 @@ impl Issue {
     #[inline(always)]
     pub fn subtasks(&self, loading_settings: IssueLoadingSettings) -> APIResult<Vec<Issue>> {
         let ids = self.data.subtasks(&self.api)?;
-        let issues = loading_settings.load_issues(
-            self.api.clone(), ids.clone(), self.caching_policy
-        )?;
+        let issues = loading_settings
+            .with_default_prefetch_policy(self.prefetch_policy)
+            .load_issues(self.api.clone(), ids.clone(), self.caching_policy)?;
         assert_eq!(issues.len(), ids.len(), "Failed to retrieve all subtasks");
         Ok(issues)
     }
@@ -200,37 +358,37 @@ impl Issue {
     }
 
     #[inline(always)]
-    pub fn date_created(&self) -> APIResult<&String> {
+    pub fn date_created(&self) -> APIResult<String> {
         self.data.date_created(&self.api)
     }
 
     #[inline(always)]
-    pub fn date_updated(&self) -> APIResult<&String> {
+    pub fn date_updated(&self) -> APIResult<String> {
         self.data.date_updated(&self.api)
     }
 
     #[inline(always)]
-    pub fn date_resolved(&self) -> APIResult<&Option<String>> {
+    pub fn date_resolved(&self) -> APIResult<Option<String>> {
         self.data.date_resolved(&self.api)
     }
 
     #[inline(always)]
-    pub fn labels(&self) -> APIResult<&Vec<String>> {
+    pub fn labels(&self) -> APIResult<Vec<String>> {
         self.data.labels(&self.api)
     }
 
     #[inline(always)]
-    pub fn components(&self) -> APIResult<&Vec<String>> {
+    pub fn components(&self) -> APIResult<Vec<String>> {
         self.data.components(&self.api)
     }
 
     #[inline(always)]
-    pub fn affected_versions(&self) -> APIResult<&Vec<String>> {
+    pub fn affected_versions(&self) -> APIResult<Vec<String>> {
         self.data.affected_versions(&self.api)
     }
 
     #[inline(always)]
-    pub fn fix_versions(&self) -> APIResult<&Vec<String>> {
+    pub fn fix_versions(&self) -> APIResult<Vec<String>> {
         self.data.fix_versions(&self.api)
     }
 }
@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+use serde_json::{Map, Value};
+use crate::errors::{APIError, APIResult};
+use crate::labels::Label;
+use crate::util::CacheLockError;
+
+/// Name of the built-in schema corresponding to `Label`.
+pub const ARCHITECTURAL_SCHEMA: &str = "architectural";
+
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LabelFieldType {
+    Boolean,
+    Number{
+        #[serde(default)] min: Option<f64>,
+        #[serde(default)] max: Option<f64>
+    },
+    Category{options: Vec<String>},
+    Text
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct LabelField {
+    pub name: String,
+    #[serde(flatten)] pub field_type: LabelFieldType,
+    #[serde(default = "default_required")] pub required: bool
+}
+
+fn default_required() -> bool {
+    true
+}
+
+/// A named set of typed fields which together make up a label.
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct LabelSchema {
+    name: String,
+    fields: Vec<LabelField>
+}
+
+#[allow(unused)]
+impl LabelSchema {
+    pub fn new(name: String, fields: Vec<LabelField>) -> APIResult<Self> {
+        let mut seen = std::collections::HashSet::new();
+        for field in fields.iter() {
+            if !seen.insert(field.name.as_str()) {
+                let msg = format!("Duplicate field \"{}\" in label schema \"{}\"", field.name, name);
+                return Err(APIError::ValidationError(msg));
+            }
+        }
+        Ok(Self{name, fields})
+    }
+
+    /// The schema of the `existence`/`executive`/`property` labels.
+    pub fn architectural() -> Self {
+        let fields = ["existence", "executive", "property"]
+            .into_iter()
+            .map(|name| LabelField{
+                name: name.to_string(),
+                field_type: LabelFieldType::Boolean,
+                required: true
+            })
+            .collect();
+        Self{name: ARCHITECTURAL_SCHEMA.to_string(), fields}
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn fields(&self) -> &Vec<LabelField> {
+        &self.fields
+    }
+
+    pub fn validate(&self, label: &Map<String, Value>) -> APIResult<()> {
+        for key in label.keys() {
+            if !self.fields.iter().any(|f| &f.name == key) {
+                return Err(self.error(format!("unknown field \"{key}\"")));
+            }
+        }
+        for field in self.fields.iter() {
+            match label.get(&field.name) {
+                None | Some(Value::Null) => {
+                    if field.required {
+                        return Err(self.error(format!("missing field \"{}\"", field.name)));
+                    }
+                }
+                Some(value) => self.validate_field(field, value)?
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_field(&self, field: &LabelField, value: &Value) -> APIResult<()> {
+        let valid = match (&field.field_type, value) {
+            (LabelFieldType::Boolean, Value::Bool(_)) => true,
+            (LabelFieldType::Text, Value::String(_)) => true,
+            (LabelFieldType::Category{options}, Value::String(s)) => {
+                if !options.contains(s) {
+                    let msg = format!("invalid value \"{s}\" for field \"{}\"", field.name);
+                    return Err(self.error(msg));
+                }
+                true
+            },
+            (LabelFieldType::Number{min, max}, Value::Number(n)) => {
+                let x = n.as_f64().unwrap_or(f64::NAN);
+                if min.is_some_and(|m| x < m) || max.is_some_and(|m| x > m) {
+                    let msg = format!("value {x} for field \"{}\" is out of range", field.name);
+                    return Err(self.error(msg));
+                }
+                true
+            },
+            _ => false
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(self.error(format!("invalid type for field \"{}\": {value}", field.name)))
+        }
+    }
+
+    /// Extract the label for this schema from a `manual-labels` payload.
+    /// The architectural label is stored at the top level of the payload;
+    /// labels of other schemas are stored under the name of the schema.
+    pub(crate) fn extract(&self, payload: &Map<String, Value>) -> APIResult<Option<Map<String, Value>>> {
+        let label = if self.name == ARCHITECTURAL_SCHEMA {
+            let label = self.fields
+                .iter()
+                .filter_map(|f| payload.get(&f.name).map(|v| (f.name.clone(), v.clone())))
+                .collect::<Map<_, _>>();
+            if label.is_empty() { None } else { Some(label) }
+        } else {
+            match payload.get(&self.name) {
+                None | Some(Value::Null) => None,
+                Some(Value::Object(label)) => Some(label.clone()),
+                Some(other) => return Err(self.error(format!("expected an object, got {other}")))
+            }
+        };
+        if let Some(ref label) = label {
+            self.validate(label)?;
+        }
+        Ok(label)
+    }
+
+    /// Inverse of `extract`; validates the label and wraps it in a payload.
+    pub(crate) fn wrap(&self, label: Map<String, Value>) -> APIResult<Map<String, Value>> {
+        let mut payload = Map::new();
+        self.merge_into(&mut payload, label)?;
+        Ok(payload)
+    }
+
+    /// Validate the label and store it in an existing `manual-labels` payload,
+    /// replacing any previous label for this schema. Labels of other
+    /// schemas in the payload are left untouched.
+    pub(crate) fn merge_into(&self,
+                             payload: &mut Map<String, Value>,
+                             label: Map<String, Value>) -> APIResult<()> {
+        self.validate(&label)?;
+        if self.name == ARCHITECTURAL_SCHEMA {
+            for field in self.fields.iter() {
+                payload.remove(&field.name);
+            }
+            payload.extend(label);
+        } else {
+            payload.insert(self.name.clone(), Value::Object(label));
+        }
+        Ok(())
+    }
+
+    fn error(&self, msg: String) -> APIError {
+        APIError::ValidationError(format!("Label does not match schema \"{}\": {msg}", self.name))
+    }
+}
+
+impl From<Label> for Map<String, Value> {
+    fn from(value: Label) -> Self {
+        let mut map = Map::new();
+        map.insert("existence".to_string(), Value::Bool(value.existence()));
+        map.insert("executive".to_string(), Value::Bool(value.executive()));
+        map.insert("property".to_string(), Value::Bool(value.property()));
+        map
+    }
+}
+
+impl TryFrom<&Map<String, Value>> for Label {
+    type Error = APIError;
+
+    fn try_from(value: &Map<String, Value>) -> Result<Self, Self::Error> {
+        LabelSchema::architectural().validate(value)?;
+        let get = |name: &str| value.get(name).and_then(Value::as_bool).unwrap_or(false);
+        Ok(Label::new(get("existence"), get("executive"), get("property")))
+    }
+}
+
+/// Registry of all label schemas known to a repository.
+/// Always contains the architectural schema.
+#[allow(unused)]
+#[derive(Debug)]
+pub(crate) struct LabelSchemaRegistry {
+    schemas: RwLock<HashMap<String, LabelSchema>>
+}
+
+#[allow(unused)]
+impl LabelSchemaRegistry {
+    pub(crate) fn new() -> Self {
+        let mut schemas = HashMap::new();
+        schemas.insert(ARCHITECTURAL_SCHEMA.to_string(), LabelSchema::architectural());
+        Self{schemas: RwLock::new(schemas)}
+    }
+
+    pub(crate) fn register(&self, schema: LabelSchema) -> APIResult<()> {
+        if schema.name == ARCHITECTURAL_SCHEMA {
+            let msg = format!("The \"{ARCHITECTURAL_SCHEMA}\" schema cannot be replaced");
+            return Err(APIError::ValidationError(msg));
+        }
+        self.schemas
+            .write()
+            .map_err(|_| CacheLockError{})?
+            .insert(schema.name.clone(), schema);
+        Ok(())
+    }
+
+    pub(crate) fn get(&self, name: &str) -> APIResult<LabelSchema> {
+        self.schemas
+            .read()
+            .map_err(|_| CacheLockError{})?
+            .get(name)
+            .cloned()
+            .ok_or_else(|| APIError::GenericError(format!("Unknown label schema: {name}")))
+    }
+
+    pub(crate) fn all(&self) -> APIResult<Vec<LabelSchema>> {
+        let schemas = self.schemas
+            .read()
+            .map_err(|_| CacheLockError{})?
+            .values()
+            .cloned()
+            .collect();
+        Ok(schemas)
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use serde_json::{Map, Value};
+use crate::api_core::IssueAPI;
+use crate::config::{CachingPolicy, IssueLoadingSettings, PrefetchPolicy};
+use crate::errors::{APIError, APIResult};
+use crate::issues::Issue;
+use crate::label_schemas::ARCHITECTURAL_SCHEMA;
+use crate::labels::Label;
+use crate::query::{Query, QueryCMP};
+use crate::review::ReviewOutcome;
+
+const HAS_LABEL_TAG: &str = "has-label";
+const NEEDS_REVIEW_TAG: &str = "needs-review";
+
+/// Work staged for a single issue, which is sent to the server on commit.
+#[allow(unused)]
+#[derive(Debug, Clone, Default)]
+struct StagedWork {
+    labels: Vec<(String, Map<String, Value>)>,
+    comments: Vec<String>,
+    review: Option<bool>
+}
+
+impl StagedWork {
+    fn is_empty(&self) -> bool {
+        self.labels.is_empty() && self.comments.is_empty() && self.review.is_none()
+    }
+}
+
+/// A change which was applied to the server during a commit,
+/// and how to revert it.
+#[derive(Debug)]
+enum Undo {
+    RestoreLabel{issue: String, previous: Option<Map<String, Value>>},
+    RemoveTag{issue: String, tag: String},
+    DeleteComment{issue: String, comment: String},
+    Review{issue: String, in_review: bool}
+}
+
+#[allow(unused)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LabellingProgress {
+    pub total: usize,
+    pub labelled: usize,
+    pub pending: usize,
+    pub committed: usize
+}
+
+/// A batch of unlabelled issues, together with the labels, comments,
+/// and review markers set for them by the user.
+/// Nothing is sent to the server until `commit` is called.
+/// If the commit fails halfway, all changes made by it are reverted
+/// (on a best-effort basis), and the staged work is kept so that
+/// the commit can be retried.
+#[allow(unused)]
+#[derive(Debug)]
+pub struct LabellingSession {
+    api: Arc<IssueAPI>,
+    issues: Vec<Issue>,
+    staged: HashMap<String, StagedWork>,
+    committed: HashSet<String>
+}
+
+#[allow(unused)]
+impl LabellingSession {
+    /// Start a session with at most `batch_size` issues matching `query`
+    /// which do not have a label yet.
+    pub(crate) fn start(api: Arc<IssueAPI>,
+                        query: Option<Query>,
+                        batch_size: usize,
+                        loading: IssueLoadingSettings,
+                        label_caching: CachingPolicy,
+                        prefetching: PrefetchPolicy) -> APIResult<Self> {
+        if batch_size == 0 {
+            return Err(APIError::GenericError("Batch size must be positive".to_string()));
+        }
+        let unlabelled = Query::Tag(QueryCMP::Ne, HAS_LABEL_TAG.to_string());
+        let query = match query {
+            Some(q) => Query::And(vec![q, unlabelled]),
+            None => unlabelled
+        };
+        let mut ids = api.search(query)?;
+        ids.truncate(batch_size);
+        let issues = if ids.is_empty() {
+            Vec::new()
+        } else {
+            loading.with_default_prefetch_policy(prefetching)
+                .load_issues(api.clone(), ids, label_caching)?
+        };
+        Ok(Self{api, issues, staged: HashMap::new(), committed: HashSet::new()})
+    }
+
+    pub fn issues(&self) -> &Vec<Issue> {
+        &self.issues
+    }
+
+    pub fn progress(&self) -> LabellingProgress {
+        let labelled = self.issues
+            .iter()
+            .filter(|i| {
+                self.committed.contains(i.ident()) ||
+                    self.staged.get(i.ident()).is_some_and(|w| !w.labels.is_empty())
+            })
+            .count();
+        LabellingProgress{
+            total: self.issues.len(),
+            labelled,
+            pending: self.staged.len(),
+            committed: self.committed.len()
+        }
+    }
+
+    fn staged_for(&mut self, issue: &Issue) -> APIResult<&mut StagedWork> {
+        if !self.issues.iter().any(|i| i == issue) {
+            let msg = format!("Issue {} is not part of this labelling session", issue.ident());
+            return Err(APIError::GenericError(msg));
+        }
+        Ok(self.staged.entry(issue.ident().clone()).or_default())
+    }
+
+    /// Stage a label for the given schema. The label is validated immediately.
+    pub fn set_label(&mut self,
+                     issue: &Issue,
+                     schema: &str,
+                     label: Map<String, Value>) -> APIResult<()> {
+        self.api.label_schemas().get(schema)?.validate(&label)?;
+        let work = self.staged_for(issue)?;
+        work.labels.retain(|(s, _)| s != schema);
+        work.labels.push((schema.to_string(), label));
+        Ok(())
+    }
+
+    pub fn set_manual_label(&mut self, issue: &Issue, label: Label) -> APIResult<()> {
+        self.set_label(issue, ARCHITECTURAL_SCHEMA, label.into())
+    }
+
+    pub fn add_comment(&mut self, issue: &Issue, text: String) -> APIResult<()> {
+        self.staged_for(issue)?.comments.push(text);
+        Ok(())
+    }
+
+    pub fn mark_for_review(&mut self, issue: &Issue, in_review: bool) -> APIResult<()> {
+        self.staged_for(issue)?.review = Some(in_review);
+        Ok(())
+    }
+
+    /// Drop all staged, uncommitted work for the given issue.
+    pub fn discard(&mut self, issue: &Issue) {
+        self.staged.remove(issue.ident());
+    }
+
+    /// Send all staged work to the server.
+    pub fn commit(&mut self) -> APIResult<()> {
+        self.staged.retain(|_, work| !work.is_empty());
+        if self.staged.is_empty() {
+            return Ok(());
+        }
+        let mut undo = Vec::new();
+        let mut done = Vec::new();
+        for issue in self.issues.iter() {
+            if let Some(work) = self.staged.get(issue.ident()) {
+                if let Err(e) = self.apply(issue, work, &mut undo) {
+                    return Err(self.rollback(undo, e));
+                }
+                done.push(issue.ident().clone());
+            }
+        }
+        for ident in done {
+            self.staged.remove(&ident);
+            self.committed.insert(ident);
+        }
+        Ok(())
+    }
+
+    fn apply(&self, issue: &Issue, work: &StagedWork, undo: &mut Vec<Undo>) -> APIResult<()> {
+        let ident = issue.ident().clone();
+        let tags = issue.get_tags()?;
+        if !work.labels.is_empty() {
+            let previous = self.api
+                .get_manual_labels_raw(vec![ident.clone()])?
+                .remove(&ident);
+            undo.push(Undo::RestoreLabel{issue: ident.clone(), previous: previous.clone()});
+            issue.merge_labels(previous.unwrap_or_default(), work.labels.clone())?;
+            if !tags.iter().any(|t| t == HAS_LABEL_TAG) {
+                issue.add_tag(HAS_LABEL_TAG.to_string())?;
+                undo.push(Undo::RemoveTag{issue: ident.clone(), tag: HAS_LABEL_TAG.to_string()});
+            }
+        }
+        if let Some(in_review) = work.review {
+            let was_in_review = tags.iter().any(|t| t == NEEDS_REVIEW_TAG);
+            if in_review != was_in_review {
+                if in_review {
+                    issue.start_review(None)?;
+                } else {
+                    issue.finish_review(ReviewOutcome::Confirmed)?;
+                }
+                undo.push(Undo::Review{issue: ident.clone(), in_review: was_in_review});
+            }
+        }
+        for text in work.comments.iter() {
+            let comment = self.api.add_labeling_comment_to_issue(ident.clone(), text.clone(), None)?;
+            undo.push(Undo::DeleteComment{issue: ident.clone(), comment});
+        }
+        Ok(())
+    }
+
+    fn rollback(&self, undo: Vec<Undo>, cause: APIError) -> APIError {
+        let mut failures = Vec::new();
+        for step in undo.into_iter().rev() {
+            let result = match step {
+                Undo::RestoreLabel{issue, previous} => {
+                    // The label cached by the issue is the one being rolled back
+                    if let Some(i) = self.issues.iter().find(|i| i.ident() == &issue) {
+                        i.invalidate_cached_label();
+                    }
+                    match previous {
+                        Some(label) => self.api.update_manual_label_raw(issue, label),
+                        // There is no endpoint to delete a label; removing the
+                        // `has-label` tag (a separate undo step) hides it instead.
+                        None => Ok(())
+                    }
+                },
+                Undo::RemoveTag{issue, tag} => self.api.remove_tag_from_issue(issue, tag),
+                Undo::DeleteComment{issue, comment} => self.api.delete_labeling_comment(issue, comment),
+                Undo::Review{issue, in_review: true} => self.api.start_issue_review(issue, None),
+                Undo::Review{issue, in_review: false} => {
+                    ReviewOutcome::Confirmed
+                        .into_payload(&self.api)
+                        .and_then(|p| self.api.finish_issue_review(issue, p))
+                }
+            };
+            if let Err(e) = result {
+                tracing::warn!(error = %e, "failed to roll back labelling session change");
+                failures.push(e.to_string());
+            }
+        }
+        if failures.is_empty() {
+            cause
+        } else {
+            let msg = format!(
+                "Commit of labelling session failed ({cause}); rolling back also failed: {}",
+                failures.join("; ")
+            );
+            APIError::GenericError(msg)
+        }
+    }
+}
@@ -13,31 +13,78 @@ mod errors;
 mod models;
 mod files;
 mod projects;
+mod metrics;
+mod throttle;
+mod prefetch;
+mod sessions;
+mod label_schemas;
+mod labelling;
+mod agreement;
+mod review;
+mod comment_search;
+mod predictions;
+mod prediction_stream;
+mod evaluation;
+mod performance;
+mod comparison;
+mod model_configs;
+mod provenance;
+mod transfers;
 
 pub use repository::IssueRepository;
 pub use errors::APIResult;
 pub use query::{Query, QueryCMP};
+pub use metrics::RequestStats;
+pub use api_core::IssueFields;
+pub use issues::IssueSnapshot;
+pub use label_schemas::{LabelField, LabelFieldType, LabelSchema};
+pub use labelling::{LabellingProgress, LabellingSession};
+pub use agreement::{cohens_kappa, fleiss_kappa, AgreementReport, LabelRecord};
+pub use review::{Review, ReviewOutcome};
+pub use tags::{BulkTagReport, TagDiff, TagType};
+pub use comments::{CommentEdit, CommentRecord, CommentThread, CommentThreadRecord};
+pub use comment_search::CommentFilter;
+pub use predictions::{ClassPrediction, Prediction};
+pub use prediction_stream::PredictionStream;
+pub use evaluation::{ClassMetrics, ConfusionMatrix, EvaluationReport};
+pub use performance::{aggregate_records, config_hash, MetricSummary, PerformanceRecord};
+pub use comparison::{compare_runs, compare_versions, paired_t_test, MetricComparison, RunComparison};
+pub use model_configs::{diff_configs, ConfigChange, ConfigChangeKind, ConfigDiff, ConfigSchema};
+pub use provenance::{Provenance, ProvenanceMatches};
+pub use transfers::ProgressCallback;
 
 
 #[cfg(feature = "pyo3")]
 mod python {
     use std::collections::HashMap;
     use std::hash::{Hash, Hasher};
+    use std::sync::Arc;
     use pyo3::basic::CompareOp;
     use pyo3::prelude::*;
     use pyo3::create_exception;
     use pyo3::exceptions::{PyException, PyTypeError, PyValueError};
     use pyo3::types::{IntoPyDict, PyBool, PyDict, PyFloat, PyInt, PyList, PyLong, PyString, PyTuple, PyType};
     use serde_json::{Map, Number, Value};
-    use crate::comments::Comment;
-    use crate::config::{CachingPolicy, ConfigHandlingPolicy, IssueAttribute, IssueLoadingSettings};
+    use crate::comments::{Comment, CommentThread};
+    use crate::config::{CachingPolicy, ConfigHandlingPolicy, IssueAttribute, IssueLoadingSettings, PrefetchPolicy};
     use crate::embedding::Embedding;
     use crate::errors::APIError;
-    use crate::issues::Issue;
+    use crate::issues::{Issue, IssueSnapshot};
     use crate::labels::Label;
+    use crate::label_schemas::{LabelField, LabelSchema};
+    use crate::labelling::LabellingSession;
+    use crate::review::{Review, ReviewOutcome};
     use crate::models::{Model, ModelVersion, TestRun};
+    use crate::predictions::{ClassPrediction, Prediction};
+    use crate::prediction_stream::PredictionStream;
+    use crate::evaluation::EvaluationReport;
+    use crate::performance::PerformanceRecord;
+    use crate::comparison::{compare_runs, compare_versions, RunComparison};
+    use crate::model_configs::ConfigSchema;
+    use crate::provenance::{Provenance, ProvenanceMatches};
+    use crate::transfers::ProgressCallback;
     use crate::projects::Project;
-    use crate::tags::{Tag, TagType};
+    use crate::tags::{Tag, TagType, DEFAULT_TAG_CHUNK_SIZE};
     use crate::errors::APIResult;
     use crate::files::File;
     use crate::repository::Repo;
@@ -49,6 +96,9 @@ mod python {
     create_exception!(issue_api, InvalidTokenException, IssueAPIError);
     create_exception!(issue_api, HTTPException, IssueAPIError);
     create_exception!(issue_api, LibraryException, IssueAPIError);
+    create_exception!(issue_api, ValidationException, IssueAPIError);
+    create_exception!(issue_api, NotFoundException, HTTPException);
+    create_exception!(issue_api, ChecksumMismatchException, IssueAPIError);
 
     #[inline(always)]
     fn api2py_error<T>(e: APIResult<T>) -> PyResult<T> {
@@ -65,6 +115,10 @@ mod python {
                     },
                     APIError::IDParsingError(_) => IssueAPIError::new_err(inner.to_string()),
                     APIError::LibraryError(_) => LibraryException::new_err(inner.to_string()),
+                    APIError::ValidationError(_) => ValidationException::new_err(inner.to_string()),
+                    // A subclass of HTTPException, so existing handlers for 404s still apply
+                    APIError::NotFound(_) => NotFoundException::new_err((inner.to_string(), 404u16)),
+                    APIError::ChecksumMismatch{..} => ChecksumMismatchException::new_err(inner.to_string()),
                     APIError::GenericError(_) => IssueAPIError::new_err(inner.to_string())
                 }
             })
@@ -101,6 +155,21 @@ mod python {
         }
     }
 
+    /// Progress callback calling `progress(done, total)`, e.g. to update a tqdm bar.
+    /// Exceptions raised by the callback are printed, but do not abort the transfer.
+    fn py_progress_callback(progress: Option<PyObject>) -> Option<ProgressCallback> {
+        progress.map(|callback| {
+            let wrapped: ProgressCallback = Arc::new(move |done, total| {
+                Python::with_gil(|py| {
+                    if let Err(e) = callback.call1(py, (done, total)) {
+                        e.print(py);
+                    }
+                })
+            });
+            wrapped
+        })
+    }
+
     fn py_to_json(obj: &PyAny) -> PyResult<Value> {
         #[allow(clippy::if_same_then_else)]
         if obj.is_instance_of::<PyDict>()? {
@@ -150,6 +219,55 @@ mod python {
         Ok(converted)
     }
 
+    /// Accept either an `Issue` or an issue ID.
+    fn extract_issue_id(obj: &PyAny) -> PyResult<String> {
+        if let Ok(issue) = obj.extract::<PyRef<PyIssue>>() {
+            Ok(issue.issue.ident().clone())
+        } else if let Ok(id) = obj.extract::<String>() {
+            Ok(id)
+        } else {
+            Err(PyTypeError::new_err("Expected an Issue or an issue ID"))
+        }
+    }
+
+    fn bulk_tag_report_to_py(py: Python<'_>, report: BulkTagReport) -> PyObject {
+        let failed = report.failed
+            .into_iter()
+            .map(|(id, e)| (id, e.to_string()))
+            .collect::<HashMap<_, _>>();
+        let mut result: HashMap<&str, PyObject> = HashMap::new();
+        result.insert("succeeded", report.succeeded.into_py(py));
+        result.insert("failed", failed.into_py(py));
+        result.into_py(py)
+    }
+
+    /// Unless `strict` is false, fail if the versions of any model could not be listed.
+    fn provenance_matches_to_py(matches: ProvenanceMatches, strict: bool) -> PyResult<Vec<PyVersion>> {
+        if strict && !matches.is_complete() {
+            let failed = matches.failed
+                .iter()
+                .map(|(model, e)| format!("{model}: {e}"))
+                .collect::<Vec<_>>();
+            let msg = format!("Failed to list versions of some models: {}", failed.join("; "));
+            return Err(IssueAPIError::new_err(msg));
+        }
+        Ok(matches.versions.into_iter().map(|v| PyVersion{inner: v}).collect())
+    }
+
+    fn parse_tag_type(tag_type: String) -> TagType {
+        match tag_type.as_str() {
+            "custom" => TagType::Custom,
+            _ => TagType::from(tag_type)
+        }
+    }
+
+    fn tag_type_to_py(tag_type: &TagType) -> String {
+        match tag_type {
+            TagType::Custom => "custom".to_string(),
+            other => other.as_str().to_string()
+        }
+    }
+
     #[pyclass(name="IssueRepository")]
     #[allow(unused)]
     struct PyIssueRepository {
@@ -181,6 +299,17 @@ mod python {
         }
     }
 
+    fn parse_prefetch_policy(prefetch_policy: &str) -> PyResult<PrefetchPolicy> {
+        match prefetch_policy {
+            "no_prefetching" => Ok(PrefetchPolicy::NoPrefetching),
+            "prefetch_siblings" => Ok(PrefetchPolicy::PrefetchSiblings),
+            _ => {
+                let text = format!("Invalid prefetch policy: {}", prefetch_policy);
+                Err(IssueAPIError::new_err(text))
+            }
+        }
+    }
+
     #[pymethods]
     impl PyIssueRepository {
         #[new]
@@ -189,15 +318,18 @@ mod python {
             credentials=None,
             label_caching_policy="no_caching",
             config_handling_policy="read_fetch_write_fetch",
+            prefetch_policy="no_prefetching",
             allow_self_signed_certificates=false
         ))]
         fn __new__(url: String,
                    credentials: Option<(String, String)>,
                    label_caching_policy: &str,
                    config_handling_policy: &str,
+                   prefetch_policy: &str,
                    allow_self_signed_certificates: bool) -> PyResult<Self> {
             let caching = parse_caching_handling(label_caching_policy)?;
             let config_handling = parse_config_policy(config_handling_policy)?;
+            let prefetching = parse_prefetch_policy(prefetch_policy)?;
             let (repo, auth) = if let Some((username, password)) = credentials {
                 (
                     IssueRepository::new(url.clone(),
@@ -217,7 +349,8 @@ mod python {
                     false
                 )
             };
-            Ok(Self{url, authenticated: auth, repo: api2py_error(repo)?})
+            let repo = api2py_error(repo)?.with_prefetch_policy(prefetching);
+            Ok(Self{url, authenticated: auth, repo})
         }
 
         #[classmethod]
@@ -226,6 +359,7 @@ mod python {
             token,
             label_caching_policy="no_caching",
             config_handling_policy="read_fetch_write_fetch",
+            prefetch_policy="no_prefetching",
             allow_self_signed_certificates=false
             ))]
         fn from_token(_cls: &PyType,
@@ -233,9 +367,11 @@ mod python {
                       token: String,
                       label_caching_policy: &str,
                       config_handling_policy: &str,
+                      prefetch_policy: &str,
                       allow_self_signed_certificates: bool) -> PyResult<Self> {
             let caching = parse_caching_handling(label_caching_policy)?;
             let config_handling = parse_config_policy(config_handling_policy)?;
+            let prefetching = parse_prefetch_policy(prefetch_policy)?;
             let repo = IssueRepository::new_with_token(
                 url.clone(),
                 token,
@@ -243,7 +379,8 @@ mod python {
                 config_handling,
                 allow_self_signed_certificates
             );
-            Ok(PyIssueRepository{url, authenticated: true, repo: api2py_error(repo)?})
+            let repo = api2py_error(repo)?.with_prefetch_policy(prefetching);
+            Ok(PyIssueRepository{url, authenticated: true, repo})
         }
 
         fn __repr__(&self) -> PyResult<String> {
@@ -253,6 +390,11 @@ mod python {
             Ok(text)
         }
 
+        fn issue_from_dict(&self, data: &PyAny) -> PyResult<PyIssue> {
+            let snapshot = parse_issue_snapshot(data)?;
+            Ok(PyIssue{issue: self.repo.restore_issue(snapshot)})
+        }
+
         #[pyo3(signature=(/, q, *, attributes=vec![], load_labels=false))]
         fn search(&self, q: PyQuery, attributes: Vec<String>, load_labels: bool) -> PyResult<Vec<PyIssue>> {
             let settings = IssueLoadingSettings::new(
@@ -267,6 +409,134 @@ mod python {
             Ok(py_issues)
         }
 
+        fn labelling_comments(&self, query: PyQuery) -> PyResult<HashMap<String, Vec<PyComment>>> {
+            let comments = api2py_error(self.repo.labelling_comments(query.get_query()?))?
+                .into_iter()
+                .map(|(id, comments)| {
+                    (id, comments.into_iter().map(|c| PyComment{inner: c}).collect())
+                })
+                .collect();
+            Ok(comments)
+        }
+
+        #[pyo3(signature=(query, *, author=None, contains=None, pattern=None, case_sensitive=false,
+                          after=None, before=None, attributes=Vec::new(), load_labels=false))]
+        #[allow(clippy::too_many_arguments)]
+        fn search_labelling_comments(&self,
+                                     query: PyQuery,
+                                     author: Option<String>,
+                                     contains: Option<String>,
+                                     pattern: Option<String>,
+                                     case_sensitive: bool,
+                                     after: Option<String>,
+                                     before: Option<String>,
+                                     attributes: Vec<String>,
+                                     load_labels: bool) -> PyResult<Vec<(PyIssue, PyComment)>> {
+            let mut filter = CommentFilter::new();
+            if let Some(author) = author {
+                filter = filter.author(author);
+            }
+            match (contains, pattern) {
+                (Some(_), Some(_)) => {
+                    return Err(PyValueError::new_err("Cannot use both `contains` and `pattern`"));
+                },
+                (Some(needle), None) => filter = filter.contains(needle, case_sensitive),
+                (None, Some(p)) => filter = api2py_error(filter.matches(&p))?,
+                (None, None) => {}
+            }
+            if let Some(after) = after {
+                filter = api2py_error(filter.after(&after))?;
+            }
+            if let Some(before) = before {
+                filter = api2py_error(filter.before(&before))?;
+            }
+            let settings = IssueLoadingSettings::new(
+                parse_issue_attributes(attributes)?, load_labels
+            );
+            let result = self.repo.search_labelling_comments(query.get_query()?, &filter, settings);
+            let pairs = api2py_error(result)?
+                .into_iter()
+                .map(|(issue, comment)| (PyIssue{issue}, PyComment{inner: comment}))
+                .collect();
+            Ok(pairs)
+        }
+
+        fn export_labelling_comments(&self, query: PyQuery, path: String) -> PyResult<()> {
+            api2py_error(self.repo.export_labelling_comments(query.get_query()?, path))
+        }
+
+        fn issues_in_review(&self) -> PyResult<Vec<PyReview>> {
+            let reviews = api2py_error(self.repo.issues_in_review())?
+                .into_iter()
+                .map(|r| PyReview{inner: r})
+                .collect();
+            Ok(reviews)
+        }
+
+        #[pyo3(signature=(issues, reason=None))]
+        fn bulk_start_review(&self, issues: Vec<PyRef<PyIssue>>, reason: Option<String>) -> PyResult<()> {
+            api2py_error(self.repo.bulk_start_review(issues.iter().map(|i| &i.issue), reason))
+        }
+
+        // Issues are hashed by their ID only, which never changes.
+        #[allow(clippy::mutable_key_type)]
+        #[pyo3(signature=(reviews, *, schema="architectural"))]
+        fn bulk_finish_review(&self,
+                              reviews: Vec<(PyRef<PyIssue>, Option<&PyAny>)>,
+                              schema: &str) -> PyResult<()> {
+            let mut outcomes = HashMap::with_capacity(reviews.len());
+            for (issue, label) in reviews.iter() {
+                outcomes.insert(&issue.issue, parse_review_outcome(*label, schema)?);
+            }
+            api2py_error(self.repo.bulk_finish_review(outcomes))
+        }
+
+        #[pyo3(signature=(query, *, schema="architectural"))]
+        fn label_agreement(&self, py: Python<'_>, query: PyQuery, schema: &str) -> PyResult<Vec<PyObject>> {
+            let reports = api2py_error(self.repo.label_agreement(query.get_query()?, schema))?
+                .into_iter()
+                .map(|r| {
+                    let mut result: HashMap<&str, PyObject> = HashMap::new();
+                    result.insert("dimension", r.dimension.into_py(py));
+                    result.insert("issues", r.issues.into_py(py));
+                    result.insert("annotators", r.annotators.into_py(py));
+                    result.insert("pairwise_cohens_kappa", r.pairwise_cohens_kappa.into_py(py));
+                    result.insert("fleiss_kappa", r.fleiss_kappa.into_py(py));
+                    result.into_py(py)
+                })
+                .collect();
+            Ok(reports)
+        }
+
+        #[pyo3(signature=(query=None, *, batch_size=50, attributes=Vec::new(), load_labels=false))]
+        fn start_labelling_session(&self,
+                                   query: Option<PyQuery>,
+                                   batch_size: usize,
+                                   attributes: Vec<String>,
+                                   load_labels: bool) -> PyResult<PyLabellingSession> {
+            let settings = IssueLoadingSettings::new(
+                parse_issue_attributes(attributes)?, load_labels
+            );
+            let query = match query {
+                Some(q) => Some(q.get_query()?),
+                None => None
+            };
+            let session = api2py_error(
+                self.repo.start_labelling_session(query, batch_size, settings)
+            )?;
+            Ok(PyLabellingSession{inner: session})
+        }
+
+        fn prefetch(&self, issues: Vec<PyRef<PyIssue>>, attributes: Vec<String>) -> PyResult<()> {
+            let attributes = parse_issue_attributes(attributes)?;
+            api2py_error(self.repo.prefetch(issues.iter().map(|i| &i.issue), attributes))
+        }
+
+        #[pyo3(signature=(batch_size, *, workers=4))]
+        fn set_issue_data_batching(&self, batch_size: usize, workers: usize) -> PyResult<()> {
+            api2py_error(self.repo.set_issue_data_batching(batch_size, workers))
+        }
+
         #[getter]
         fn projects(&self) -> PyResult<Vec<PyProject>> {
             let projects = api2py_error(self.repo.projects())?
@@ -311,16 +581,91 @@ mod python {
             Ok(tags)
         }
 
-        fn add_new_tag(&self, name: String, description: String) -> PyResult<()> {
-            api2py_error(self.repo.add_new_tag(name, description))
+        #[pyo3(signature=(name, description, *, tag_type=None))]
+        fn add_new_tag(&self, name: String, description: String, tag_type: Option<String>) -> PyResult<()> {
+            let result = match tag_type {
+                None => self.repo.add_new_tag(name, description),
+                Some(t) => self.repo.add_new_tag_of_type(name, description, parse_tag_type(t))
+            };
+            api2py_error(result)
+        }
+
+        fn tags_by_type(&self, tag_type: String) -> PyResult<Vec<PyTag>> {
+            let tags = api2py_error(self.repo.tags_by_type(parse_tag_type(tag_type)))?
+                .into_iter()
+                .map(|t| PyTag{inner: t})
+                .collect();
+            Ok(tags)
+        }
+
+        #[getter]
+        fn authors(&self) -> PyResult<Vec<String>> {
+            api2py_error(self.repo.authors())
+        }
+
+        #[pyo3(signature=(version, query, *, attributes=Vec::new()))]
+        fn predictions_with_labels(&self,
+                                   version: &PyVersion,
+                                   query: PyQuery,
+                                   attributes: Vec<String>) -> PyResult<Vec<(PyIssue, PyPrediction, Option<PyLabel>)>> {
+            let settings = IssueLoadingSettings::new(parse_issue_attributes(attributes)?, true);
+            let joined = api2py_error(
+                self.repo.predictions_with_labels(&version.inner, query.get_query()?, settings)
+            )?;
+            let converted = joined.into_iter()
+                .map(|(issue, prediction, label)| {
+                    (PyIssue{issue}, PyPrediction{inner: prediction}, label.map(|l| PyLabel{inner: l}))
+                })
+                .collect();
+            Ok(converted)
+        }
+
+        #[pyo3(signature=(author, *, attributes=Vec::new(), load_labels=false))]
+        fn issues_by_author(&self,
+                            author: String,
+                            attributes: Vec<String>,
+                            load_labels: bool) -> PyResult<Vec<PyIssue>> {
+            let settings = IssueLoadingSettings::new(
+                parse_issue_attributes(attributes)?, load_labels
+            );
+            let issues = api2py_error(self.repo.issues_by_author(author, settings))?
+                .into_iter()
+                .map(|issue| PyIssue{issue})
+                .collect();
+            Ok(issues)
+        }
+
+        fn get_tag(&self, name: String) -> PyResult<PyTag> {
+            Ok(PyTag{inner: api2py_error(self.repo.get_tag(name))?})
+        }
+
+        #[pyo3(signature=(tags, *, chunk_size=DEFAULT_TAG_CHUNK_SIZE))]
+        fn bulk_add_tags(&self, py: Python<'_>, tags: &PyDict, chunk_size: usize) -> PyResult<PyObject> {
+            let mut diffs = HashMap::with_capacity(tags.len());
+            for (issue, add) in tags {
+                diffs.insert(extract_issue_id(issue)?, TagDiff{add: add.extract()?, remove: Vec::new()});
+            }
+            Ok(bulk_tag_report_to_py(py, self.repo.bulk_apply_tag_diff_by_id(diffs, chunk_size)))
+        }
+
+        #[pyo3(signature=(tags, *, chunk_size=DEFAULT_TAG_CHUNK_SIZE))]
+        fn bulk_remove_tags(&self, py: Python<'_>, tags: &PyDict, chunk_size: usize) -> PyResult<PyObject> {
+            let mut diffs = HashMap::with_capacity(tags.len());
+            for (issue, remove) in tags {
+                diffs.insert(extract_issue_id(issue)?, TagDiff{add: Vec::new(), remove: remove.extract()?});
+            }
+            Ok(bulk_tag_report_to_py(py, self.repo.bulk_apply_tag_diff_by_id(diffs, chunk_size)))
         }
 
-        // fn bulk_add_tags(&self, i: &Vec<PyIssue>) -> PyResult<()> {
-        //     let payload = tags_per_issue.into_iter()
-        //         .map(|(k, v)| (&k.issue, v))
-        //         .collect();
-        //     api2py_error(self.repo.bulk_add_tags(payload))
-        // }
+        #[pyo3(signature=(diffs, *, chunk_size=DEFAULT_TAG_CHUNK_SIZE))]
+        fn bulk_apply_tag_diff(&self, py: Python<'_>, diffs: &PyDict, chunk_size: usize) -> PyResult<PyObject> {
+            let mut converted = HashMap::with_capacity(diffs.len());
+            for (issue, diff) in diffs {
+                let (add, remove) = diff.extract::<(Vec<String>, Vec<String>)>()?;
+                converted.insert(extract_issue_id(issue)?, TagDiff{add, remove});
+            }
+            Ok(bulk_tag_report_to_py(py, self.repo.bulk_apply_tag_diff_by_id(converted, chunk_size)))
+        }
 
         #[getter]
         fn embeddings(&self) -> PyResult<Vec<PyEmbedding>> {
@@ -377,6 +722,28 @@ mod python {
             Ok(m)
         }
 
+        fn get_model_version(&self, model_id: String, version_id: String) -> PyResult<PyVersion> {
+            let inner = api2py_error(self.repo.get_model_version(model_id, version_id))?;
+            Ok(PyVersion{inner})
+        }
+
+        fn get_test_run(&self, model_id: String, run_id: String) -> PyResult<PyPerformance> {
+            let inner = api2py_error(self.repo.get_test_run(model_id, run_id))?;
+            Ok(PyPerformance{inner})
+        }
+
+        #[pyo3(signature=(embedding_id, *, strict=true))]
+        fn versions_using_embedding(&self, embedding_id: String, strict: bool) -> PyResult<Vec<PyVersion>> {
+            let matches = api2py_error(self.repo.versions_using_embedding(&embedding_id))?;
+            provenance_matches_to_py(matches, strict)
+        }
+
+        #[pyo3(signature=(file_id, *, strict=true))]
+        fn versions_using_file(&self, file_id: String, strict: bool) -> PyResult<Vec<PyVersion>> {
+            let matches = api2py_error(self.repo.versions_using_file(&file_id))?;
+            provenance_matches_to_py(matches, strict)
+        }
+
         fn add_model(&self, name: String, config: HashMap<String, &PyAny>) -> PyResult<PyModel> {
             let mut converted = HashMap::with_capacity(config.len());
             for (k, v) in config {
@@ -411,6 +778,63 @@ mod python {
         fn delete_file(&self, file: &PyFile) -> PyResult<()> {
             api2py_error(self.repo.remove_file(file.inner.clone()))
         }
+
+        fn set_model_config_schema(&self, schema: Option<&PyAny>) -> PyResult<()> {
+            let schema = match schema {
+                Some(s) => Some(api2py_error(ConfigSchema::new(py_to_json(s)?))?),
+                None => None
+            };
+            api2py_error(self.repo.set_model_config_schema(schema))
+        }
+
+        #[getter]
+        fn model_config_schema(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+            let schema = api2py_error(self.repo.model_config_schema())?;
+            Ok(schema.map(|s| json_to_py(py, s)))
+        }
+
+        fn register_label_schema(&self, schema: &PyLabelSchema) -> PyResult<()> {
+            api2py_error(self.repo.register_label_schema(schema.inner.clone()))
+        }
+
+        fn label_schemas(&self) -> PyResult<Vec<PyLabelSchema>> {
+            let schemas = api2py_error(self.repo.label_schemas())?
+                .into_iter()
+                .map(|s| PyLabelSchema{inner: s})
+                .collect();
+            Ok(schemas)
+        }
+
+        fn get_label_schema(&self, name: String) -> PyResult<PyLabelSchema> {
+            Ok(PyLabelSchema{inner: api2py_error(self.repo.get_label_schema(&name))?})
+        }
+
+        fn stats(&self, py: Python<'_>) -> PyResult<PyObject> {
+            let stats = self.repo.stats();
+            let mut result: HashMap<&str, PyObject> = HashMap::new();
+            result.insert("requests", stats.requests.into_py(py));
+            result.insert("failed_requests", stats.failed_requests.into_py(py));
+            result.insert("bytes_sent", stats.bytes_sent.into_py(py));
+            result.insert("bytes_received", stats.bytes_received.into_py(py));
+            result.insert("request_time", stats.request_time.as_secs_f64().into_py(py));
+            result.insert("cache_hits", stats.cache_hits.into_py(py));
+            result.insert("cache_misses", stats.cache_misses.into_py(py));
+            Ok(result.into_py(py))
+        }
+
+        fn reset_stats(&self) -> PyResult<()> {
+            self.repo.reset_stats();
+            Ok(())
+        }
+
+        #[pyo3(signature=(requests_per_second, *, burst=1))]
+        fn set_rate_limit(&self, requests_per_second: Option<f64>, burst: u32) -> PyResult<()> {
+            api2py_error(self.repo.set_rate_limit(requests_per_second, burst))
+        }
+
+        fn set_max_in_flight(&self, limit: Option<usize>) -> PyResult<()> {
+            api2py_error(self.repo.set_max_in_flight(limit))
+        }
     }
 
     #[pyclass(name="Query")]
@@ -513,6 +937,17 @@ mod python {
             )
         }
 
+        fn author(&self, name: String) -> PyResult<Self> {
+            if self.query.is_some() {
+                return Err(
+                    IssueAPIError::new_err(
+                        "issues_api.Query.author cannot be called on a tag-query."
+                    )
+                )
+            }
+            Ok(Self{query: Some(Query::Author(name))})
+        }
+
         fn to_json(&self, py: Python<'_>) -> PyResult<PyObject> {
             match self.query {
                 None => Ok(py.None()),
@@ -584,18 +1019,373 @@ mod python {
         }
     }
 
-    #[pyclass(name="Issue")]
+    #[pyclass(name="Prediction")]
+    #[allow(unused)]
+    #[derive(Debug, Clone)]
+    struct PyPrediction {
+        inner: Prediction
+    }
+
+    fn py_bool(value: bool) -> &'static str {
+        if value { "True" } else { "False" }
+    }
+
+    fn py_optional_float(value: Option<f64>) -> String {
+        value.map_or_else(|| "None".to_string(), |v| v.to_string())
+    }
+
+    #[pymethods]
+    impl PyPrediction {
+        #[new]
+        #[pyo3(signature=(
+            existence, executive, property, *,
+            existence_probability=None,
+            executive_probability=None,
+            property_probability=None,
+            confidence=None
+        ))]
+        fn __new__(existence: bool,
+                   executive: bool,
+                   property: bool,
+                   existence_probability: Option<f64>,
+                   executive_probability: Option<f64>,
+                   property_probability: Option<f64>,
+                   confidence: Option<f64>) -> PyResult<Self> {
+            let inner = Prediction::new(
+                ClassPrediction::new(existence, existence_probability),
+                ClassPrediction::new(executive, executive_probability),
+                ClassPrediction::new(property, property_probability),
+                confidence
+            );
+            api2py_error(inner.validate())?;
+            Ok(Self{inner})
+        }
+
+        #[classmethod]
+        fn from_dict(_cls: &PyType, data: &PyAny) -> PyResult<Self> {
+            Ok(Self{inner: api2py_error(Prediction::from_json(py_to_json(data)?))?})
+        }
+
+        fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+            Ok(json_to_py(py, self.inner.to_json()))
+        }
+
+        fn __repr__(&self) -> PyResult<String> {
+            let text = format!(
+                "issue_api.Prediction(existence={}, executive={}, property={}, \
+                existence_probability={}, executive_probability={}, property_probability={}, \
+                confidence={})",
+                py_bool(self.inner.existence.predicted),
+                py_bool(self.inner.executive.predicted),
+                py_bool(self.inner.property.predicted),
+                py_optional_float(self.inner.existence.probability),
+                py_optional_float(self.inner.executive.probability),
+                py_optional_float(self.inner.property.probability),
+                py_optional_float(self.inner.confidence)
+            );
+            Ok(text)
+        }
+
+        fn __richcmp__(&self, other: PyRef<PyPrediction>, op: CompareOp) -> Py<PyAny> {
+            let py = other.py();
+            match op {
+                CompareOp::Eq => (self.inner == other.inner).into_py(py),
+                CompareOp::Ne => (self.inner != other.inner).into_py(py),
+                _ => py.NotImplemented(),
+            }
+        }
+
+        #[getter]
+        fn existence(&self) -> PyResult<bool> {
+            Ok(self.inner.existence.predicted)
+        }
+
+        #[getter]
+        fn executive(&self) -> PyResult<bool> {
+            Ok(self.inner.executive.predicted)
+        }
+
+        #[getter]
+        fn property(&self) -> PyResult<bool> {
+            Ok(self.inner.property.predicted)
+        }
+
+        #[getter]
+        fn existence_probability(&self) -> PyResult<Option<f64>> {
+            Ok(self.inner.existence.probability)
+        }
+
+        #[getter]
+        fn executive_probability(&self) -> PyResult<Option<f64>> {
+            Ok(self.inner.executive.probability)
+        }
+
+        #[getter]
+        fn property_probability(&self) -> PyResult<Option<f64>> {
+            Ok(self.inner.property.probability)
+        }
+
+        #[getter]
+        fn confidence(&self) -> PyResult<Option<f64>> {
+            Ok(self.inner.confidence)
+        }
+
+        #[getter]
+        fn label(&self) -> PyResult<PyLabel> {
+            Ok(PyLabel{inner: self.inner.label()})
+        }
+
+        #[getter]
+        fn non_architectural(&self) -> PyResult<bool> {
+            Ok(self.inner.non_architectural())
+        }
+    }
+
+    #[pyclass(name="LabelSchema")]
+    #[allow(unused)]
+    #[derive(Debug, Clone)]
+    struct PyLabelSchema {
+        inner: LabelSchema
+    }
+
+    fn json_object(obj: &PyAny) -> PyResult<Map<String, Value>> {
+        match py_to_json(obj)? {
+            Value::Object(map) => Ok(map),
+            _ => Err(PyTypeError::new_err("Expected a dictionary"))
+        }
+    }
+
+    #[pymethods]
+    impl PyLabelSchema {
+        #[new]
+        fn __new__(name: String, fields: Vec<&PyAny>) -> PyResult<Self> {
+            let mut converted: Vec<LabelField> = Vec::with_capacity(fields.len());
+            for field in fields {
+                let parsed = serde_json::from_value(py_to_json(field)?)
+                    .map_err(|e| ValidationException::new_err(e.to_string()))?;
+                converted.push(parsed);
+            }
+            Ok(Self{inner: api2py_error(LabelSchema::new(name, converted))?})
+        }
+
+        fn __repr__(&self) -> PyResult<String> {
+            let names = self.inner.fields()
+                .iter()
+                .map(|f| f.name.clone())
+                .collect::<Vec<_>>()
+                .join(", ");
+            Ok(format!("issue_api.LabelSchema(name={}, fields=[{}])", self.inner.name(), names))
+        }
+
+        #[getter]
+        fn name(&self) -> PyResult<String> {
+            Ok(self.inner.name().clone())
+        }
+
+        #[getter]
+        fn fields(&self, py: Python<'_>) -> PyResult<PyObject> {
+            let fields = serde_json::to_value(self.inner.fields())
+                .map_err(|e| LibraryException::new_err(e.to_string()))?;
+            Ok(json_to_py(py, fields))
+        }
+
+        fn validate(&self, label: &PyAny) -> PyResult<()> {
+            api2py_error(self.inner.validate(&json_object(label)?))
+        }
+    }
+
+    /// `None` means the label was confirmed; otherwise, the label was
+    /// changed to the given `Label` or dictionary.
+    fn parse_review_outcome(label: Option<&PyAny>, schema: &str) -> PyResult<ReviewOutcome> {
+        let label = match label {
+            None => return Ok(ReviewOutcome::Confirmed),
+            Some(obj) if obj.is_none() => return Ok(ReviewOutcome::Confirmed),
+            Some(obj) => match obj.extract::<PyLabel>() {
+                Ok(label) => Map::from(label.inner),
+                Err(_) => json_object(obj)?
+            }
+        };
+        Ok(ReviewOutcome::Changed{schema: schema.to_string(), label})
+    }
+
+    #[pyclass(name="Review")]
+    #[allow(unused)]
+    #[derive(Clone)]
+    struct PyReview {
+        inner: Review
+    }
+
+    #[pymethods]
+    impl PyReview {
+        fn __repr__(&self) -> PyResult<String> {
+            Ok(format!("<Review|issue={}>", self.inner.issue_id()))
+        }
+
+        #[getter]
+        fn issue_id(&self) -> PyResult<String> {
+            Ok(self.inner.issue_id().clone())
+        }
+
+        #[getter]
+        fn reason(&self) -> PyResult<Option<String>> {
+            Ok(self.inner.reason().cloned())
+        }
+
+        #[getter]
+        fn requested_by(&self) -> PyResult<Option<String>> {
+            Ok(self.inner.requested_by().cloned())
+        }
+
+        #[getter]
+        fn reviewers(&self) -> PyResult<Vec<String>> {
+            Ok(self.inner.reviewers().clone())
+        }
+
+        #[getter]
+        fn comments(&self) -> PyResult<Vec<PyComment>> {
+            let comments = api2py_error(self.inner.comments())?
+                .into_iter()
+                .map(|c| PyComment{inner: c})
+                .collect();
+            Ok(comments)
+        }
+
+        #[pyo3(signature=(attributes=Vec::new(), load_labels=false))]
+        fn issue(&self, attributes: Vec<String>, load_labels: bool) -> PyResult<PyIssue> {
+            let settings = IssueLoadingSettings::new(
+                parse_issue_attributes(attributes)?, load_labels
+            );
+            Ok(PyIssue{issue: api2py_error(self.inner.issue(settings))?})
+        }
+
+        #[pyo3(signature=(label=None, *, schema="architectural"))]
+        fn finish(&self, label: Option<&PyAny>, schema: &str) -> PyResult<()> {
+            api2py_error(self.inner.finish(parse_review_outcome(label, schema)?))
+        }
+    }
+
+    #[pyclass(name="LabellingSession")]
+    #[allow(unused)]
+    struct PyLabellingSession {
+        inner: LabellingSession
+    }
+
+    #[pymethods]
+    impl PyLabellingSession {
+        #[getter]
+        fn issues(&self) -> PyResult<Vec<PyIssue>> {
+            let mut issues = Vec::with_capacity(self.inner.issues().len());
+            for issue in self.inner.issues() {
+                issues.push(PyIssue{issue: api2py_error(issue.share())?});
+            }
+            Ok(issues)
+        }
+
+        fn progress(&self, py: Python<'_>) -> PyResult<PyObject> {
+            let progress = self.inner.progress();
+            let mut result: HashMap<&str, usize> = HashMap::new();
+            result.insert("total", progress.total);
+            result.insert("labelled", progress.labelled);
+            result.insert("pending", progress.pending);
+            result.insert("committed", progress.committed);
+            Ok(result.into_py(py))
+        }
+
+        #[pyo3(signature=(issue, label, *, schema="architectural"))]
+        fn set_label(&mut self, issue: &PyIssue, label: &PyAny, schema: &str) -> PyResult<()> {
+            if let Ok(label) = label.extract::<PyLabel>() {
+                if schema == "architectural" {
+                    return api2py_error(self.inner.set_manual_label(&issue.issue, label.inner));
+                }
+            }
+            api2py_error(self.inner.set_label(&issue.issue, schema, json_object(label)?))
+        }
+
+        fn add_comment(&mut self, issue: &PyIssue, text: String) -> PyResult<()> {
+            api2py_error(self.inner.add_comment(&issue.issue, text))
+        }
+
+        #[pyo3(signature=(issue, in_review=true))]
+        fn mark_for_review(&mut self, issue: &PyIssue, in_review: bool) -> PyResult<()> {
+            api2py_error(self.inner.mark_for_review(&issue.issue, in_review))
+        }
+
+        fn discard(&mut self, issue: &PyIssue) -> PyResult<()> {
+            self.inner.discard(&issue.issue);
+            Ok(())
+        }
+
+        fn commit(&mut self) -> PyResult<()> {
+            api2py_error(self.inner.commit())
+        }
+    }
+
+    #[pyclass(name="Issue", module="issue_db_api.issue_api")]
     #[allow(unused)]
     #[derive(Debug, PartialEq, Eq, Hash)]
     struct PyIssue {
         issue: Issue
     }
 
+    fn parse_issue_snapshot(state: &PyAny) -> PyResult<IssueSnapshot> {
+        serde_json::from_value(py_to_json(state)?)
+            .map_err(|e| PyValueError::new_err(format!("Invalid issue state: {e}")))
+    }
+
     #[pymethods]
     impl PyIssue {
+        /// Issues cannot be created directly; the optional `state`
+        /// argument only exists to support unpickling.
+        /// See `__setstate__` for how the issue is bound to a repository.
         #[new]
-        fn __new__() -> PyResult<Self> {
-            Err(IssueAPIError::new_err("issue_api.Issue cannot be instantiated directly"))
+        #[pyo3(signature=(state=None))]
+        fn __new__(state: Option<&PyAny>) -> PyResult<Self> {
+            match state {
+                None => Err(IssueAPIError::new_err("issue_api.Issue cannot be instantiated directly")),
+                Some(s) => {
+                    let issue = api2py_error(Issue::restore(parse_issue_snapshot(s)?))?;
+                    Ok(Self{issue})
+                }
+            }
+        }
+
+        #[classmethod]
+        #[pyo3(signature=(data, repository=None))]
+        fn from_dict(_cls: &PyType, data: &PyAny, repository: Option<&PyIssueRepository>) -> PyResult<Self> {
+            let snapshot = parse_issue_snapshot(data)?;
+            let issue = match repository {
+                Some(r) => r.repo.restore_issue(snapshot),
+                None => api2py_error(Issue::restore(snapshot))?
+            };
+            Ok(Self{issue})
+        }
+
+        fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+            let snapshot = api2py_error(self.issue.snapshot())?;
+            let json = serde_json::to_value(snapshot)
+                .map_err(|e| LibraryException::new_err(e.to_string()))?;
+            Ok(json_to_py(py, json))
+        }
+
+        fn __getstate__(&self, py: Python<'_>) -> PyResult<PyObject> {
+            self.to_dict(py)
+        }
+
+        /// Bind the unpickled issue to the most recently created
+        /// `IssueRepository` for the same server in this process.
+        /// If there is none (e.g. in a freshly started worker process),
+        /// the issue falls back to an unauthenticated, read-only connection
+        /// without label caching, and any write raises an error.
+        /// Create a repository before unpickling, or use `Issue.from_dict`
+        /// with an explicit repository, to avoid this.
+        fn __setstate__(&mut self, state: &PyAny) -> PyResult<()> {
+            self.issue = api2py_error(Issue::restore(parse_issue_snapshot(state)?))?;
+            Ok(())
+        }
+
+        fn __reduce__(&self, py: Python<'_>) -> PyResult<(PyObject, (PyObject,))> {
+            let state = self.to_dict(py)?;
+            Ok((py.get_type::<PyIssue>().into_py(py), (state,)))
         }
 
         fn __repr__(&self) -> PyResult<String> {
@@ -634,6 +1424,40 @@ mod python {
             api2py_error(self.issue.set_manual_label(value.inner))
         }
 
+        #[pyo3(signature=(schema="architectural"))]
+        fn get_label(&self, py: Python<'_>, schema: &str) -> PyResult<PyObject> {
+            let label = api2py_error(self.issue.get_label(schema))?
+                .map(Value::Object)
+                .unwrap_or(Value::Null);
+            Ok(json_to_py(py, label))
+        }
+
+        #[pyo3(signature=(label, *, schema="architectural"))]
+        fn set_label(&self, label: &PyAny, schema: &str) -> PyResult<()> {
+            api2py_error(self.issue.set_label(schema, json_object(label)?))
+        }
+
+        #[pyo3(signature=(schema="architectural"))]
+        fn get_annotator_labels(&self, py: Python<'_>, schema: &str) -> PyResult<PyObject> {
+            let labels = api2py_error(self.issue.get_annotator_labels(schema))?
+                .into_iter()
+                .map(|(author, label)| (author, json_to_py(py, Value::Object(label))))
+                .collect::<HashMap<_, _>>();
+            Ok(labels.into_py(py))
+        }
+
+        #[getter]
+        fn label_history(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+            let history = api2py_error(self.issue.label_history())?
+                .into_iter()
+                .map(|record| {
+                    let value = serde_json::to_value(record).unwrap_or(Value::Null);
+                    json_to_py(py, value)
+                })
+                .collect();
+            Ok(history)
+        }
+
         fn invalidate_cached_label(&self) -> PyResult<()> {
             self.issue.invalidate_cached_label();
             Ok(())
@@ -652,13 +1476,15 @@ mod python {
             api2py_error(self.issue.remove_tag(name))
         }
 
-        // fn start_review(&self) -> PyResult<()> {
-        //     api2py_error(self.issue.start_review())
-        // }
-        //
-        // fn finish_review(&self) -> PyResult<()> {
-        //     api2py_error(self.issue.finish_review())
-        // }
+        #[pyo3(signature=(reason=None))]
+        fn start_review(&self, reason: Option<String>) -> PyResult<()> {
+            api2py_error(self.issue.start_review(reason))
+        }
+
+        #[pyo3(signature=(label=None, *, schema="architectural"))]
+        fn finish_review(&self, label: Option<&PyAny>, schema: &str) -> PyResult<()> {
+            api2py_error(self.issue.finish_review(parse_review_outcome(label, schema)?))
+        }
 
         #[getter]
         fn is_in_review(&self) -> PyResult<bool> {
@@ -668,9 +1494,9 @@ mod python {
         #[setter]
         fn set_is_in_review(&self, value: bool) -> PyResult<()> {
             let result = if value {
-                self.issue.start_review()
+                self.issue.start_review(None)
             } else {
-                self.issue.finish_review()
+                self.issue.finish_review(ReviewOutcome::Confirmed)
             };
             api2py_error(result)
         }
@@ -684,6 +1510,15 @@ mod python {
             Ok(comments)
         }
 
+        #[getter]
+        fn labelling_comment_threads(&self) -> PyResult<Vec<PyCommentThread>> {
+            let threads = api2py_error(self.issue.get_labelling_comment_threads())?
+                .into_iter()
+                .map(|t| PyCommentThread{inner: t})
+                .collect();
+            Ok(threads)
+        }
+
         pub fn add_labelling_comment(&self, text: String) -> PyResult<()> {
            api2py_error(self.issue.add_labelling_comment(text))
         }
@@ -694,42 +1529,42 @@ mod python {
 
         #[getter]
         fn key(&self) -> PyResult<String> {
-            api2py_error(self.issue.key().map(|x| x.clone()))
+            api2py_error(self.issue.key())
         }
 
         #[getter]
         fn summary(&self) -> PyResult<String> {
-            api2py_error(self.issue.summary().map(|x| x.clone()))
+            api2py_error(self.issue.summary())
         }
 
         #[getter]
         fn description(&self) -> PyResult<String> {
-            api2py_error(self.issue.description().map(|x| x.clone()))
+            api2py_error(self.issue.description())
         }
 
         #[getter]
         fn comments(&self) -> PyResult<Vec<String>> {
-            api2py_error(self.issue.comments().map(|x| x.clone()))
+            api2py_error(self.issue.comments())
         }
 
         #[getter]
         fn status(&self) -> PyResult<String> {
-            api2py_error(self.issue.status().map(|x| x.clone()))
+            api2py_error(self.issue.status())
         }
 
         #[getter]
         fn priority(&self) -> PyResult<String> {
-            api2py_error(self.issue.priority().map(|x| x.clone()))
+            api2py_error(self.issue.priority())
         }
 
         #[getter]
         fn resolution(&self) -> PyResult<Option<String>> {
-            api2py_error(self.issue.resolution().map(|x| x.clone()))
+            api2py_error(self.issue.resolution())
         }
 
         #[getter]
         fn issue_type(&self) -> PyResult<String> {
-            api2py_error(self.issue.issue_type().map(|x| x.clone()))
+            api2py_error(self.issue.issue_type())
         }
 
         #[pyo3(signature=(*, attributes=vec![], load_labels=false))]
@@ -777,37 +1612,37 @@ mod python {
 
         #[getter]
         fn date_created(&self) -> PyResult<String> {
-            api2py_error(self.issue.date_created().map(|x| x.clone()))
+            api2py_error(self.issue.date_created())
         }
 
         #[getter]
         fn date_updated(&self) -> PyResult<String> {
-            api2py_error(self.issue.date_updated().map(|x| x.clone()))
+            api2py_error(self.issue.date_updated())
         }
 
         #[getter]
         fn date_resolved(&self) -> PyResult<Option<String>> {
-            api2py_error(self.issue.date_resolved().map(|x| x.clone()))
+            api2py_error(self.issue.date_resolved())
         }
 
         #[getter]
         fn labels(&self) -> PyResult<Vec<String>> {
-            api2py_error(self.issue.labels().map(|x| x.clone()))
+            api2py_error(self.issue.labels())
         }
 
         #[getter]
         fn components(&self) -> PyResult<Vec<String>> {
-            api2py_error(self.issue.components().map(|x| x.clone()))
+            api2py_error(self.issue.components())
         }
 
         #[getter]
         fn affected_versions(&self) -> PyResult<Vec<String>> {
-            api2py_error(self.issue.affected_versions().map(|x| x.clone()))
+            api2py_error(self.issue.affected_versions())
         }
 
         #[getter]
         fn fix_versions(&self) -> PyResult<Vec<String>> {
-            api2py_error(self.issue.fix_versions().map(|x| x.clone()))
+            api2py_error(self.issue.fix_versions())
         }
     }
 
@@ -838,6 +1673,91 @@ mod python {
         fn set_body(&mut self, text: String) -> PyResult<()> {
             api2py_error(self.inner.update_text(text))
         }
+
+        #[getter]
+        fn identifier(&self) -> PyResult<String> {
+            Ok(self.inner.identifier().clone())
+        }
+
+        #[getter]
+        fn issue_id(&self) -> PyResult<String> {
+            Ok(self.inner.issue_id().clone())
+        }
+
+        #[getter]
+        fn timestamp(&self) -> PyResult<Option<String>> {
+            Ok(self.inner.timestamp().cloned())
+        }
+
+        #[getter]
+        fn edit_history(&self, py: Python<'_>) -> PyResult<PyObject> {
+            let edits = serde_json::to_value(self.inner.edit_history())
+                .map_err(|e| LibraryException::new_err(e.to_string()))?;
+            Ok(json_to_py(py, edits))
+        }
+
+        #[getter]
+        fn reply_to(&self) -> PyResult<Option<String>> {
+            Ok(self.inner.reply_to().cloned())
+        }
+
+        #[getter]
+        fn resolved(&self) -> PyResult<bool> {
+            Ok(self.inner.resolved())
+        }
+
+        #[setter]
+        fn set_resolved(&mut self, resolved: bool) -> PyResult<()> {
+            api2py_error(self.inner.set_resolved(resolved))
+        }
+
+        fn reply(&self, text: String) -> PyResult<PyComment> {
+            Ok(PyComment{inner: api2py_error(self.inner.reply(text))?})
+        }
+
+        fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+            let record = serde_json::to_value(self.inner.to_record())
+                .map_err(|e| LibraryException::new_err(e.to_string()))?;
+            Ok(json_to_py(py, record))
+        }
+    }
+
+    #[pyclass(name="CommentThread")]
+    #[allow(unused)]
+    #[derive(Clone)]
+    struct PyCommentThread {
+        inner: CommentThread
+    }
+
+    #[pymethods]
+    impl PyCommentThread {
+        fn __repr__(&self) -> PyResult<String> {
+            Ok(
+                format!("<CommentThread|id={}|replies={}>",
+                        self.inner.comment.identifier(),
+                        self.inner.replies.len())
+            )
+        }
+
+        #[getter]
+        fn comment(&self) -> PyResult<PyComment> {
+            Ok(PyComment{inner: self.inner.comment.clone()})
+        }
+
+        #[getter]
+        fn replies(&self) -> PyResult<Vec<PyCommentThread>> {
+            let replies = self.inner.replies
+                .iter()
+                .map(|t| PyCommentThread{inner: t.clone()})
+                .collect();
+            Ok(replies)
+        }
+
+        fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+            let record = serde_json::to_value(self.inner.to_record())
+                .map_err(|e| LibraryException::new_err(e.to_string()))?;
+            Ok(json_to_py(py, record))
+        }
     }
 
     #[pyclass(name="Tag")]
@@ -884,12 +1804,38 @@ mod python {
 
         #[getter]
         fn tag_type(&self) -> PyResult<String> {
-            let type_name = match self.inner.tag_type() {
-                TagType::Author => "author",
-                TagType::Project => "project",
-                TagType::Custom => "custom"
-            };
-            Ok(type_name.to_string())
+            Ok(tag_type_to_py(self.inner.tag_type()))
+        }
+
+        fn delete(&self) -> PyResult<()> {
+            api2py_error(self.inner.delete())
+        }
+
+        #[pyo3(signature=(attributes=Vec::new(), load_labels=false))]
+        fn issues(&self, attributes: Vec<String>, load_labels: bool) -> PyResult<Vec<PyIssue>> {
+            let settings = IssueLoadingSettings::new(
+                parse_issue_attributes(attributes)?, load_labels
+            );
+            let issues = api2py_error(self.inner.issues(settings))?
+                .into_iter()
+                .map(|issue| PyIssue{issue})
+                .collect();
+            Ok(issues)
+        }
+
+        #[getter]
+        fn usage_count(&self) -> PyResult<usize> {
+            api2py_error(self.inner.usage_count())
+        }
+
+        fn rename(&mut self, py: Python<'_>, new_name: String) -> PyResult<PyObject> {
+            let report = api2py_error(self.inner.rename(new_name))?;
+            Ok(bulk_tag_report_to_py(py, report))
+        }
+
+        fn merge_into(&self, py: Python<'_>, target: &PyTag) -> PyResult<PyObject> {
+            let report = api2py_error(self.inner.clone().merge_into(&target.inner))?;
+            Ok(bulk_tag_report_to_py(py, report))
         }
     }
 
@@ -1019,6 +1965,19 @@ mod python {
             }
         }
 
+        fn config_hash(&self) -> PyResult<String> {
+            api2py_error(self.inner.config_hash())
+        }
+
+        fn diff_config(&self, py: Python<'_>, other: &PyModel) -> PyResult<Vec<PyObject>> {
+            let diff = api2py_error(self.inner.diff_config(&other.inner))?;
+            let changes = diff.changes
+                .into_iter()
+                .map(|c| json_to_py(py, serde_json::to_value(c).expect("ConfigChange is always serializable")))
+                .collect();
+            Ok(changes)
+        }
+
         #[getter]
         fn versions(&self) -> PyResult<Vec<PyVersion>> {
             let result = api2py_error(self.inner.model_versions())?;
@@ -1033,9 +1992,17 @@ mod python {
             Ok(v)
         }
 
-        fn add_version(&self, path: String, description: Option<String>) -> PyResult<PyVersion> {
-            let version = api2py_error(self.inner.upload_version(path, description))?;
-            Ok(PyVersion{inner: version})
+        #[pyo3(signature=(path, description=None, *, progress=None))]
+        fn add_version(&self,
+                       py: Python<'_>,
+                       path: String,
+                       description: Option<String>,
+                       progress: Option<PyObject>) -> PyResult<PyVersion> {
+            let progress = py_progress_callback(progress);
+            let version = py.allow_threads(
+                || self.inner.upload_version_with_progress(path, description, progress)
+            );
+            Ok(PyVersion{inner: api2py_error(version)?})
         }
 
         fn remove_version(&self, version: &PyVersion) -> PyResult<()> {
@@ -1056,6 +2023,20 @@ mod python {
             Ok(r)
         }
 
+        #[pyo3(signature=(records, description=None))]
+        fn add_test_run_records(&self,
+                                records: Vec<&PyAny>,
+                                description: Option<String>) -> PyResult<PyPerformance> {
+            let mut converted = Vec::with_capacity(records.len());
+            for obj in records {
+                let record: PerformanceRecord = serde_json::from_value(py_to_json(obj)?)
+                    .map_err(|e| PyValueError::new_err(format!("Invalid performance record: {e}")))?;
+                converted.push(record);
+            }
+            let run = api2py_error(self.inner.store_records(converted, description))?;
+            Ok(PyPerformance{inner: run})
+        }
+
         fn add_test_run(&self, data: Vec<&PyAny>, description: Option<String>) -> PyResult<PyPerformance> {
             let mut converted = Vec::with_capacity(data.len());
             for obj in data {
@@ -1104,8 +2085,15 @@ mod python {
             Ok(self.inner.version_id())
         }
 
-        fn download(&self, path: String) -> PyResult<()> {
-            api2py_error(self.inner.download(path))
+        #[pyo3(signature=(path, *, progress=None))]
+        fn download(&self, py: Python<'_>, path: String, progress: Option<PyObject>) -> PyResult<()> {
+            let progress = py_progress_callback(progress);
+            api2py_error(py.allow_threads(|| self.inner.download_with_progress(path, progress)))
+        }
+
+        #[getter]
+        fn checksum(&self) -> PyResult<Option<String>> {
+            Ok(self.inner.checksum())
         }
 
         #[getter]
@@ -1127,10 +2115,65 @@ mod python {
             api2py_error(self.inner.store_predictions(converted))
         }
 
+        #[pyo3(signature=(issues=None))]
+        fn get_typed_predictions(&self, issues: Option<Vec<String>>) -> PyResult<HashMap<String, PyPrediction>> {
+            let predictions = api2py_error(self.inner.get_typed_predictions(issues))?
+                .into_iter()
+                .map(|(k, v)| (k, PyPrediction{inner: v}))
+                .collect();
+            Ok(predictions)
+        }
+
+        fn get_predictions_for_query(&self, query: PyQuery) -> PyResult<HashMap<String, PyPrediction>> {
+            let predictions = api2py_error(self.inner.get_predictions_for_query(query.get_query()?))?
+                .into_iter()
+                .map(|(k, v)| (k, PyPrediction{inner: v}))
+                .collect();
+            Ok(predictions)
+        }
+
+        #[pyo3(signature=(issues=None, *, typed=false))]
+        fn iter_predictions(&self, issues: Option<Vec<String>>, typed: bool) -> PyResult<PyPredictionIterator> {
+            let stream = api2py_error(self.inner.stream_predictions(issues))?;
+            Ok(PyPredictionIterator{stream, typed})
+        }
+
+        fn store_typed_predictions(&self, predictions: HashMap<String, PyPrediction>) -> PyResult<()> {
+            let converted = predictions
+                .into_iter()
+                .map(|(k, v)| (k, v.inner))
+                .collect();
+            api2py_error(self.inner.store_typed_predictions(converted))
+        }
+
         fn delete_predictions(&self) -> PyResult<()> {
             api2py_error(self.inner.delete_predictions())
         }
 
+        #[staticmethod]
+        #[pyo3(signature=(versions, query, *, threshold=0.5))]
+        fn compare(versions: Vec<PyRef<PyVersion>>,
+                   query: PyQuery,
+                   threshold: f64) -> PyResult<PyRunComparison> {
+            let versions = versions.iter().map(|v| v.inner.clone()).collect::<Vec<_>>();
+            let inner = api2py_error(compare_versions(&versions, query.get_query()?, threshold))?;
+            Ok(PyRunComparison{inner})
+        }
+
+        #[pyo3(signature=(query, *, threshold=0.5))]
+        fn evaluate(&self, py: Python<'_>, query: PyQuery, threshold: f64) -> PyResult<PyObject> {
+            let report = api2py_error(self.inner.evaluate(query.get_query()?, threshold))?;
+            Ok(json_to_py(py, report.to_json()))
+        }
+
+        #[pyo3(signature=(report, description=None))]
+        fn store_evaluation(&self, report: &PyAny, description: Option<String>) -> PyResult<PyPerformance> {
+            let report: EvaluationReport = serde_json::from_value(py_to_json(report)?)
+                .map_err(|e| PyValueError::new_err(format!("Invalid evaluation report: {e}")))?;
+            let run = api2py_error(self.inner.store_evaluation(&report, description))?;
+            Ok(PyPerformance{inner: run})
+        }
+
         #[getter]
         pub fn description(&self) -> PyResult<String> {
             Ok(self.inner.description())
@@ -1140,6 +2183,51 @@ mod python {
         pub fn set_description(&mut self, description: String) -> PyResult<()> {
             api2py_error(self.inner.update_description(description))
         }
+
+        #[getter]
+        fn provenance(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+            Ok(self.inner.provenance().map(|p| json_to_py(py, p.to_json())))
+        }
+
+        #[setter]
+        fn set_provenance(&mut self, provenance: Option<&PyAny>) -> PyResult<()> {
+            let provenance = match provenance {
+                Some(p) => Some(api2py_error(Provenance::from_json(py_to_json(p)?))?),
+                None => None
+            };
+            api2py_error(self.inner.set_provenance(provenance))
+        }
+    }
+
+    #[pyclass(name="PredictionIterator")]
+    #[allow(unused)]
+    struct PyPredictionIterator {
+        stream: PredictionStream,
+        typed: bool
+    }
+
+    #[pymethods]
+    impl PyPredictionIterator {
+        fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+            slf
+        }
+
+        fn __next__(mut slf: PyRefMut<'_, Self>, py: Python<'_>) -> PyResult<Option<PyObject>> {
+            // Do not hold the GIL while waiting for the download
+            let stream = &mut slf.stream;
+            let entry = match py.allow_threads(|| stream.next()) {
+                None => return Ok(None),
+                Some(entry) => api2py_error(entry)?
+            };
+            let (issue, raw) = entry;
+            let prediction = if slf.typed {
+                let inner = api2py_error(Prediction::from_json(raw))?;
+                PyPrediction{inner}.into_py(py)
+            } else {
+                json_to_py(py, raw)
+            };
+            Ok(Some((issue, prediction).into_py(py)))
+        }
     }
 
     #[pyclass(name="TestRun")]
@@ -1177,6 +2265,31 @@ mod python {
             Ok(converted)
         }
 
+        #[getter]
+        fn records(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+            let records = api2py_error(self.inner.records())?
+                .into_iter()
+                .map(|r| json_to_py(py, r.to_json()))
+                .collect();
+            Ok(records)
+        }
+
+        #[staticmethod]
+        #[pyo3(signature=(runs, *, split=None))]
+        fn compare(runs: Vec<PyRef<PyPerformance>>, split: Option<&str>) -> PyResult<PyRunComparison> {
+            let runs = runs.iter().map(|r| r.inner.clone()).collect::<Vec<_>>();
+            let inner = api2py_error(compare_runs(&runs, split))?;
+            Ok(PyRunComparison{inner})
+        }
+
+        #[pyo3(signature=(split=None))]
+        fn aggregate(&self, py: Python<'_>, split: Option<&str>) -> PyResult<PyObject> {
+            let summary = api2py_error(self.inner.aggregate(split))?;
+            let json = serde_json::to_value(summary)
+                .map_err(|e| LibraryException::new_err(e.to_string()))?;
+            Ok(json_to_py(py, json))
+        }
+
         #[getter]
         pub fn description(&self) -> PyResult<String> {
             Ok(self.inner.description())
@@ -1188,6 +2301,42 @@ mod python {
         }
     }
 
+    #[pyclass(name="RunComparison")]
+    #[allow(unused)]
+    struct PyRunComparison {
+        inner: RunComparison
+    }
+
+    #[pymethods]
+    impl PyRunComparison {
+        fn __str__(&self) -> PyResult<String> {
+            Ok(self.inner.to_table())
+        }
+
+        #[getter]
+        fn runs(&self) -> PyResult<Vec<String>> {
+            Ok(self.inner.runs.clone())
+        }
+
+        fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+            let json = serde_json::to_value(&self.inner)
+                .map_err(|e| LibraryException::new_err(e.to_string()))?;
+            Ok(json_to_py(py, json))
+        }
+
+        fn to_table(&self) -> PyResult<String> {
+            Ok(self.inner.to_table())
+        }
+
+        fn to_csv(&self) -> PyResult<String> {
+            Ok(self.inner.to_csv())
+        }
+
+        fn to_markdown(&self) -> PyResult<String> {
+            Ok(self.inner.to_markdown())
+        }
+    }
+
     #[pyclass(name="File")]
     #[allow(unused)]
     struct PyFile {
@@ -1341,15 +2490,25 @@ mod python {
         m.add("InvalidTokenException", py.get_type::<InvalidTokenException>())?;
         m.add("HTTPException", py.get_type::<HTTPException>())?;
         m.add("LibraryException", py.get_type::<LibraryException>())?;
+        m.add("ValidationException", py.get_type::<ValidationException>())?;
+        m.add("NotFoundException", py.get_type::<NotFoundException>())?;
+        m.add("ChecksumMismatchException", py.get_type::<ChecksumMismatchException>())?;
         m.add_class::<PyIssueRepository>()?;
         m.add_class::<PyIssue>()?;
         m.add_class::<PyQuery>()?;
         m.add_class::<PyLabel>()?;
+        m.add_class::<PyPrediction>()?;
+        m.add_class::<PyPredictionIterator>()?;
+        m.add_class::<PyLabelSchema>()?;
+        m.add_class::<PyLabellingSession>()?;
+        m.add_class::<PyReview>()?;
         m.add_class::<PyTag>()?;
         m.add_class::<PyModel>()?;
         m.add_class::<PyVersion>()?;
         m.add_class::<PyPerformance>()?;
+        m.add_class::<PyRunComparison>()?;
         m.add_class::<PyComment>()?;
+        m.add_class::<PyCommentThread>()?;
         m.add_class::<PyEmbedding>()?;
         m.add_class::<PyFile>()?;
         m.add_class::<PyRepo>()?;
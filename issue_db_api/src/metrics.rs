@@ -0,0 +1,81 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// In-memory collector for request and cache statistics.
+/// A single collector is shared by an `IssueAPI` and all objects
+/// (issues, models, ...) bound to it.
+#[allow(unused)]
+#[derive(Debug, Default)]
+pub(crate) struct MetricsCollector {
+    requests: AtomicU64,
+    failed_requests: AtomicU64,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    request_time_micros: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64
+}
+
+#[allow(unused)]
+impl MetricsCollector {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn record_request(&self,
+                                 bytes_sent: u64,
+                                 bytes_received: u64,
+                                 latency: Duration,
+                                 success: bool) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if !success {
+            self.failed_requests.fetch_add(1, Ordering::Relaxed);
+        }
+        self.bytes_sent.fetch_add(bytes_sent, Ordering::Relaxed);
+        self.bytes_received.fetch_add(bytes_received, Ordering::Relaxed);
+        self.request_time_micros.fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_cache_hit(&self) {
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_cache_miss(&self) {
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> RequestStats {
+        RequestStats{
+            requests: self.requests.load(Ordering::Relaxed),
+            failed_requests: self.failed_requests.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            request_time: Duration::from_micros(self.request_time_micros.load(Ordering::Relaxed)),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            cache_misses: self.cache_misses.load(Ordering::Relaxed)
+        }
+    }
+
+    pub(crate) fn reset(&self) {
+        self.requests.store(0, Ordering::Relaxed);
+        self.failed_requests.store(0, Ordering::Relaxed);
+        self.bytes_sent.store(0, Ordering::Relaxed);
+        self.bytes_received.store(0, Ordering::Relaxed);
+        self.request_time_micros.store(0, Ordering::Relaxed);
+        self.cache_hits.store(0, Ordering::Relaxed);
+        self.cache_misses.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Point-in-time copy of the statistics gathered by a repository.
+#[allow(unused)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct RequestStats {
+    pub requests: u64,
+    pub failed_requests: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub request_time: Duration,
+    pub cache_hits: u64,
+    pub cache_misses: u64
+}
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use jsonschema::JSONSchema;
+use serde_json::{Map, Value};
+use crate::errors::{APIError, APIResult};
+use crate::util::CacheLockError;
+
+/// JSON schema which model configs must conform to.
+#[allow(unused)]
+pub struct ConfigSchema {
+    raw: Value,
+    compiled: JSONSchema
+}
+
+#[allow(unused)]
+impl ConfigSchema {
+    pub fn new(schema: Value) -> APIResult<Self> {
+        let compiled = JSONSchema::compile(&schema)
+            .map_err(|e| APIError::ValidationError(format!("Invalid config schema: {e}")))?;
+        Ok(Self{raw: schema, compiled})
+    }
+
+    pub fn schema(&self) -> &Value {
+        &self.raw
+    }
+
+    /// Validate a config, reporting all violations at once.
+    pub fn validate(&self, config: &HashMap<String, Value>) -> APIResult<()> {
+        let instance = Value::Object(
+            config.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+        );
+        if let Err(errors) = self.compiled.validate(&instance) {
+            let messages = errors
+                .map(|e| {
+                    let path = e.instance_path.to_string();
+                    if path.is_empty() { e.to_string() } else { format!("{path}: {e}") }
+                })
+                .collect::<Vec<_>>();
+            let msg = format!("Invalid model config: {}", messages.join("; "));
+            return Err(APIError::ValidationError(msg));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for ConfigSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigSchema").field("schema", &self.raw).finish()
+    }
+}
+
+/// Schema shared by all models of a repository; `None` disables validation.
+#[allow(unused)]
+#[derive(Debug, Default)]
+pub(crate) struct ConfigSchemaSlot {
+    schema: RwLock<Option<Arc<ConfigSchema>>>
+}
+
+#[allow(unused)]
+impl ConfigSchemaSlot {
+    pub(crate) fn set(&self, schema: Option<ConfigSchema>) -> APIResult<()> {
+        *self.schema.write().map_err(|_| CacheLockError{})? = schema.map(Arc::new);
+        Ok(())
+    }
+
+    pub(crate) fn get(&self) -> APIResult<Option<Arc<ConfigSchema>>> {
+        Ok(self.schema.read().map_err(|_| CacheLockError{})?.clone())
+    }
+
+    pub(crate) fn validate(&self, config: &HashMap<String, Value>) -> APIResult<()> {
+        match self.get()? {
+            Some(schema) => schema.validate(config),
+            None => Ok(())
+        }
+    }
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum ConfigChangeKind {
+    Added{value: Value},
+    Removed{value: Value},
+    Changed{old: Value, new: Value}
+}
+
+/// A single difference between two configs.
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ConfigChange {
+    /// JSON pointer (RFC 6901) to the changed value, e.g. `/optimizer/learning-rate`.
+    pub path: String,
+    #[serde(flatten)] pub kind: ConfigChangeKind
+}
+
+/// Differences between two configs, ordered by path.
+#[allow(unused)]
+#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ConfigDiff {
+    pub changes: Vec<ConfigChange>
+}
+
+#[allow(unused)]
+impl ConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).expect("ConfigDiff is always serializable")
+    }
+}
+
+impl std::fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for change in self.changes.iter() {
+            match &change.kind {
+                ConfigChangeKind::Added{value} => writeln!(f, "+ {}: {value}", change.path)?,
+                ConfigChangeKind::Removed{value} => writeln!(f, "- {}: {value}", change.path)?,
+                ConfigChangeKind::Changed{old, new} => {
+                    writeln!(f, "~ {}: {old} -> {new}", change.path)?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Recursively compare two configs. Nested objects are compared key by key,
+/// and arrays element by element; any other difference is a change
+/// of the value as a whole.
+pub fn diff_configs(old: &HashMap<String, Value>, new: &HashMap<String, Value>) -> ConfigDiff {
+    let to_map = |config: &HashMap<String, Value>| config.iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect::<Map<String, Value>>();
+    let mut changes = Vec::new();
+    diff_objects("", &to_map(old), &to_map(new), &mut changes);
+    ConfigDiff{changes}
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn diff_objects(path: &str, old: &Map<String, Value>, new: &Map<String, Value>, changes: &mut Vec<ConfigChange>) {
+    let mut keys = old.keys().chain(new.keys()).collect::<Vec<_>>();
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        let path = child_path(path, key);
+        match (old.get(key), new.get(key)) {
+            (Some(a), Some(b)) => diff_values(path, a, b, changes),
+            (Some(a), None) => {
+                changes.push(ConfigChange{path, kind: ConfigChangeKind::Removed{value: a.clone()}})
+            },
+            (None, Some(b)) => {
+                changes.push(ConfigChange{path, kind: ConfigChangeKind::Added{value: b.clone()}})
+            },
+            (None, None) => unreachable!()
+        }
+    }
+}
+
+fn diff_values(path: String, old: &Value, new: &Value, changes: &mut Vec<ConfigChange>) {
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => diff_objects(&path, a, b, changes),
+        (Value::Array(a), Value::Array(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                let path = child_path(&path, &i.to_string());
+                let kind = match (a.get(i), b.get(i)) {
+                    (Some(x), Some(y)) => {
+                        diff_values(path, x, y, changes);
+                        continue;
+                    },
+                    (Some(x), None) => ConfigChangeKind::Removed{value: x.clone()},
+                    (None, Some(y)) => ConfigChangeKind::Added{value: y.clone()},
+                    (None, None) => unreachable!()
+                };
+                changes.push(ConfigChange{path, kind});
+            }
+        },
+        (a, b) if a != b => {
+            changes.push(ConfigChange{path, kind: ConfigChangeKind::Changed{old: a.clone(), new: b.clone()}})
+        },
+        _ => {}
+    }
+}
@@ -1,11 +1,19 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use serde_json::Value;
 use crate::api_core::IssueAPI;
 use crate::errors::APIResult;
 use crate::config::ConfigHandlingPolicy;
+use crate::query::{Query, QueryCMP};
+use crate::transfers::ProgressCallback;
 use crate::errors::APIError;
+use crate::evaluation::{evaluate_predictions, EvaluationReport};
+use crate::performance::{aggregate_records, config_hash, MetricSummary, PerformanceRecord};
+use crate::predictions::Prediction;
+use crate::model_configs::{diff_configs, ConfigDiff};
+use crate::prediction_stream::PredictionStream;
+use crate::provenance::{join_description, split_description, Provenance, VersionMetadata};
 use crate::util::CacheContainer;
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
@@ -37,12 +45,14 @@ pub(crate) struct UnboundModelVersion {
 }
 
 impl UnboundModelVersion {
-    fn into_bound_model(self, api: Arc<IssueAPI>) -> ModelVersion {
+    pub(crate) fn into_bound_model(self, api: Arc<IssueAPI>) -> ModelVersion {
+        let (description, metadata) = split_description(self.description);
         ModelVersion{
             api,
             model: self.model_id,
             version: self.version_id,
-            description: self.description
+            description,
+            metadata
         }
     }
 }
@@ -56,7 +66,7 @@ pub(crate) struct UnboundTestRun {
 }
 
 impl UnboundTestRun {
-    fn into_bound_test_run(self, api: Arc<IssueAPI>) -> TestRun {
+    pub(crate) fn into_bound_test_run(self, api: Arc<IssueAPI>) -> TestRun {
         TestRun{
             api,
             model: self.model_id,
@@ -104,10 +114,10 @@ impl Model {
                config: Option<HashMap<String, Value>>,
                config_handling: ConfigHandlingPolicy) -> Self {
         Self{
+            config: CacheContainer::new(config, api.metrics()),
             api,
             id,
             name,
-            config: CacheContainer::new(config),
             data_policy: config_handling
         }
     }
@@ -156,7 +166,19 @@ impl Model {
         }
     }
 
+    /// Hash of the current config, for use in `PerformanceRecord::config_hash`.
+    pub fn config_hash(&self) -> APIResult<String> {
+        Ok(config_hash(&self.config()?))
+    }
+
+    /// Differences between the config of this model and that of `other`,
+    /// i.e. what changes when going from this model to `other`.
+    pub fn diff_config(&self, other: &Model) -> APIResult<ConfigDiff> {
+        Ok(diff_configs(&self.config()?, &other.config()?))
+    }
+
     pub fn update_config(&mut self, config: HashMap<String, Value>) -> APIResult<()> {
+        self.api.model_config_schema().validate(&config)?;
         let name = match self.data_policy {
             ConfigHandlingPolicy::ReadLocalWriteNoFetch => self.name.clone(),
             _ => {
@@ -169,26 +191,42 @@ impl Model {
     }
 
     pub fn upload_version(&self, path: String, description: Option<String>) -> APIResult<ModelVersion> {
-        let id = self.api.upload_model_version(
-            self.id.clone(), path
+        self.upload_version_with_progress(path, description, None)
+    }
+
+    /// Upload a model binary. Its checksum is stored along with
+    /// the description, so that downloads can be verified.
+    ///
+    /// The server has no way to receive the checksum with the binary,
+    /// so it is stored by a second request. If that request fails,
+    /// the returned error contains the ID of the uploaded version,
+    /// which exists but has neither a description nor a checksum.
+    pub fn upload_version_with_progress(&self,
+                                        path: String,
+                                        description: Option<String>,
+                                        progress: Option<ProgressCallback>) -> APIResult<ModelVersion> {
+        let (id, checksum) = self.api.upload_model_version(
+            self.id.clone(), path, progress
         )?;
-        let v = if let Some(text) = description {
-            self.api.update_version_description(self.id.clone(),
-                                                id.clone(),
-                                                text.clone())?;
-            ModelVersion{
-                api: self.api.clone(),
-                model: self.id.clone(),
-                version: id,
-                description: text
-            }
-        } else {
-            ModelVersion{
-                api: self.api.clone(),
-                model: self.id.clone(),
-                version: id,
-                description: String::new()
-            }
+        let description = description.unwrap_or_default();
+        let metadata = VersionMetadata{provenance: None, sha256: Some(checksum)};
+        let stored = self.api.update_version_description(
+            self.id.clone(), id.clone(), join_description(&description, &metadata)
+        );
+        if let Err(e) = stored {
+            let msg = format!(
+                "Version {id} of model {} was uploaded, but its description and checksum \
+                could not be stored, so downloads of it cannot be verified: {e}",
+                self.id
+            );
+            return Err(APIError::GenericError(msg));
+        }
+        let v = ModelVersion{
+            api: self.api.clone(),
+            model: self.id.clone(),
+            version: id,
+            description,
+            metadata
         };
         Ok(v)
     }
@@ -208,17 +246,21 @@ impl Model {
         Ok(converted)
     }
 
+    /// Versions of this model whose provenance matches the given predicate.
+    pub fn versions_with_provenance<F>(&self, predicate: F) -> APIResult<Vec<ModelVersion>>
+    where
+        F: Fn(&Provenance) -> bool
+    {
+        let versions = self.model_versions()?
+            .into_iter()
+            .filter(|v| v.metadata.provenance.as_ref().is_some_and(&predicate))
+            .collect();
+        Ok(versions)
+    }
+
     pub fn get_version_by_id(&self, id: String) -> APIResult<ModelVersion> {
-        let versions = self.model_versions()?;
-        let version = versions.into_iter()
-            .find(|v| v.version == id);
-        match version {
-            None => {
-                let text = format!("Could not find version with ID {id}");
-                Err(APIError::GenericError(text))
-            },
-            Some(v) => Ok(v)
-        }
+        let version = self.api.get_model_version(self.id.clone(), id)?;
+        Ok(version.into_bound_model(self.api.clone()))
     }
 
     pub fn model_runs(&self) -> APIResult<Vec<TestRun>> {
@@ -231,39 +273,19 @@ impl Model {
     }
 
     pub fn store_run(&self, data: Vec<Value>, description: Option<String>) -> APIResult<TestRun> {
-        let id = self.api.store_model_performance(self.id.clone(), data)?;
-        let run = if let Some(text) = description {
-            self.api.update_performance_description(self.id.clone(),
-                                                    id.clone(),
-                                                    text.clone())?;
-            TestRun{
-                api: self.api.clone(),
-                model: self.id.clone(),
-                run: id,
-                description: text
-            }
-        } else {
-            TestRun{
-                api: self.api.clone(),
-                model: self.id.clone(),
-                run: id,
-                description: String::new()
-            }
-        };
-        Ok(run)
+        TestRun::store(self.api.clone(), self.id.clone(), data, description)
+    }
+
+    pub fn store_records(&self,
+                         records: Vec<PerformanceRecord>,
+                         description: Option<String>) -> APIResult<TestRun> {
+        let data = records.iter().map(PerformanceRecord::to_json).collect();
+        self.store_run(data, description)
     }
 
     pub fn get_run_by_id(&self, id: String) -> APIResult<TestRun> {
-        let runs = self.model_runs()?;
-        let run = runs.into_iter()
-            .find(|r| r.run == id);
-        match run {
-            None => {
-                let text = format!("Could not find test run with ID {id}");
-                Err(APIError::GenericError(text))
-            },
-            Some(v) => Ok(v)
-        }
+        let run = self.api.get_test_run(self.id.clone(), id)?;
+        Ok(run.into_bound_test_run(self.api.clone()))
     }
 
     pub fn delete_run(&self, run: TestRun) -> APIResult<()> {
@@ -277,7 +299,8 @@ pub struct ModelVersion {
     api: Arc<IssueAPI>,
     model: String,
     version: String,
-    description: String
+    description: String,
+    metadata: VersionMetadata
 }
 
 impl PartialEq for ModelVersion {
@@ -311,16 +334,52 @@ impl ModelVersion {
 
     pub fn update_description(&mut self, description: String) -> APIResult<()> {
         self.api.update_version_description(
-            self.model.clone(), self.version.clone(), description.clone()
+            self.model.clone(),
+            self.version.clone(),
+            join_description(&description, &self.metadata)
         )?;
         self.description = description;
         Ok(())
     }
 
+    pub fn provenance(&self) -> Option<Provenance> {
+        self.metadata.provenance.clone()
+    }
+
+    /// Provenance is stored along with the description of the version.
+    pub fn set_provenance(&mut self, provenance: Option<Provenance>) -> APIResult<()> {
+        let metadata = VersionMetadata{provenance, ..self.metadata.clone()};
+        self.api.update_version_description(
+            self.model.clone(),
+            self.version.clone(),
+            join_description(&self.description, &metadata)
+        )?;
+        self.metadata = metadata;
+        Ok(())
+    }
+
+    /// SHA-256 checksum of the model binary, computed when it was uploaded.
+    /// `None` for versions uploaded before checksums were recorded.
+    pub fn checksum(&self) -> Option<String> {
+        self.metadata.sha256.clone()
+    }
+
     pub fn download(&self, path: String) -> APIResult<()> {
+        self.download_with_progress(path, None)
+    }
+
+    /// Download the model binary. If the checksum of the version is known,
+    /// an earlier interrupted download of this version to the same path
+    /// is resumed if possible, and the downloaded file is verified before
+    /// it is moved to `path`. Otherwise, the download starts from scratch.
+    pub fn download_with_progress(&self,
+                                  path: String,
+                                  progress: Option<ProgressCallback>) -> APIResult<()> {
         self.api.download_model_version(self.model.clone(),
                                         self.version.clone(),
-                                        path)
+                                        path,
+                                        self.metadata.sha256.as_deref(),
+                                        progress)
     }
 
     pub fn get_predictions(&self, issues: Option<Vec<String>>) -> APIResult<HashMap<String, Value>> {
@@ -331,9 +390,71 @@ impl ModelVersion {
         self.api.store_predictions(self.model.clone(), self.version.clone(), predictions)
     }
 
+    /// Like `get_predictions`, but parses and validates every prediction.
+    pub fn get_typed_predictions(&self, issues: Option<Vec<String>>) -> APIResult<HashMap<String, Prediction>> {
+        self.stream_predictions(issues)?.typed().collect()
+    }
+
+    /// Predictions for all issues matching the given query.
+    pub fn get_predictions_for_query(&self, query: Query) -> APIResult<HashMap<String, Prediction>> {
+        let ids = self.api.search(query)?;
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        self.get_typed_predictions(Some(ids))
+    }
+
+    /// Iterate over `(issue_id, prediction)` pairs while they are being downloaded.
+    pub fn stream_predictions(&self, issues: Option<Vec<String>>) -> APIResult<PredictionStream> {
+        self.api.stream_predictions(self.model.clone(), self.version.clone(), issues)
+    }
+
+    /// Store predictions without materializing the full upload in memory.
+    pub fn store_prediction_stream<I>(&self, predictions: I) -> APIResult<()>
+    where
+        I: Iterator<Item=(String, Value)> + Send + 'static
+    {
+        self.api.store_prediction_stream(self.model.clone(), self.version.clone(), predictions)
+    }
+
+    /// Validate all predictions, and store them if all are valid.
+    pub fn store_typed_predictions(&self, predictions: HashMap<String, Prediction>) -> APIResult<()> {
+        for (issue, prediction) in predictions.iter() {
+            prediction.validate()
+                .map_err(|e| APIError::ValidationError(format!("Prediction for issue {issue}: {e}")))?;
+        }
+        self.store_prediction_stream(predictions.into_iter().map(|(k, v)| (k, v.to_json())))
+    }
+
     pub fn delete_predictions(&self) -> APIResult<()> {
         self.api.delete_predictions(self.model.clone(), self.version.clone())
     }
+
+    /// Evaluate the predictions of this version against the
+    /// manual labels of all labelled issues matching `query`.
+    /// Class probabilities are compared against `threshold`;
+    /// classes without a probability use the predicted value.
+    pub fn evaluate(&self, query: Query, threshold: f64) -> APIResult<EvaluationReport> {
+        let query = Query::And(vec![
+            query,
+            Query::Tag(QueryCMP::Eq, "has-label".to_string())
+        ]);
+        let ids = self.api.search(query)?;
+        let (predictions, labels) = if ids.is_empty() {
+            (HashMap::new(), HashMap::new())
+        } else {
+            (self.get_typed_predictions(Some(ids.clone()))?, self.api.get_manual_labels(ids)?)
+        };
+        evaluate_predictions(self.model.clone(), self.version.clone(), threshold, &predictions, &labels)
+    }
+
+    /// Store an evaluation report as a test run of the model of this version.
+    pub fn store_evaluation(&self,
+                            report: &EvaluationReport,
+                            description: Option<String>) -> APIResult<TestRun> {
+        let data = vec![report.to_performance_record().to_json()];
+        TestRun::store(self.api.clone(), self.model.clone(), data, description)
+    }
 }
 
 #[allow(unused)]
@@ -347,6 +468,33 @@ pub struct TestRun {
 
 #[allow(unused)]
 impl TestRun {
+    /// Store a new run. The description is stored by a second request;
+    /// if that request fails, the returned error contains the ID
+    /// of the stored run, which exists but has no description.
+    fn store(api: Arc<IssueAPI>,
+             model: String,
+             data: Vec<Value>,
+             description: Option<String>) -> APIResult<Self> {
+        let run = api.store_model_performance(model.clone(), data)?;
+        let description = match description {
+            Some(text) => {
+                let stored = api.update_performance_description(
+                    model.clone(), run.clone(), text.clone()
+                );
+                if let Err(e) = stored {
+                    let msg = format!(
+                        "Test run {run} of model {model} was stored, \
+                        but its description could not be stored: {e}"
+                    );
+                    return Err(APIError::GenericError(msg));
+                }
+                text
+            },
+            None => String::new()
+        };
+        Ok(TestRun{api, model, run, description})
+    }
+
     pub fn model_id(&self) -> String {
         self.model.clone()
     }
@@ -359,6 +507,20 @@ impl TestRun {
         self.api.get_performance_data(self.model.clone(), self.run.clone())
     }
 
+    /// The performance data as typed records; see `PerformanceRecord::from_json`.
+    pub fn records(&self) -> APIResult<Vec<PerformanceRecord>> {
+        let records = self.data()?
+            .into_iter()
+            .map(PerformanceRecord::from_json)
+            .collect();
+        Ok(records)
+    }
+
+    /// Mean and standard deviation of every metric across folds; see `aggregate_records`.
+    pub fn aggregate(&self, split: Option<&str>) -> APIResult<BTreeMap<String, MetricSummary>> {
+        Ok(aggregate_records(&self.records()?, split))
+    }
+
     pub fn description(&self) -> String {
         self.description.clone()
     }
@@ -0,0 +1,285 @@
+use std::collections::{BTreeMap, HashMap};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+/// Fields of a `PerformanceRecord` which are not metrics
+/// when parsing legacy (untyped) records.
+const RECORD_FIELDS: [&str; 6] = ["fold", "split", "epoch", "config_hash", "started_at", "finished_at"];
+
+/// A single entry of the performance data of a test run,
+/// e.g. the results of one fold, or of one epoch within a fold.
+#[allow(unused)]
+#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PerformanceRecord {
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub fold: Option<u32>,
+    /// E.g. "train", "validation" or "test".
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub split: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub epoch: Option<u32>,
+    #[serde(default)] pub metrics: BTreeMap<String, f64>,
+    /// Hash of the model config used for training; see `config_hash`.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub config_hash: Option<String>,
+    /// RFC 3339 timestamps.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub started_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub finished_at: Option<String>,
+    /// Any other data, e.g. hyperparameters or detailed results.
+    /// Stored as top-level fields of the record.
+    #[serde(flatten)] pub extra: Map<String, Value>
+}
+
+#[allow(unused)]
+impl PerformanceRecord {
+    pub fn new(metrics: BTreeMap<String, f64>) -> Self {
+        Self{metrics, ..Self::default()}
+    }
+
+    /// Parse a record, accepting records stored before records were typed.
+    /// For legacy records, all top-level numbers become metrics (unless the record
+    /// has a `metrics` object), known record fields are used as such,
+    /// and everything else ends up in `extra`.
+    /// Values which are not objects are stored in `extra` under `"data"`.
+    pub fn from_json(value: Value) -> Self {
+        let is_typed = value.get("metrics").is_some_and(Value::is_object);
+        if is_typed {
+            if let Ok(record) = serde_json::from_value::<Self>(value.clone()) {
+                return record;
+            }
+        }
+        let mut object = match value {
+            Value::Object(object) => object,
+            other => {
+                let mut extra = Map::new();
+                extra.insert("data".to_string(), other);
+                return Self{extra, ..Self::default()};
+            }
+        };
+        let mut known = Map::new();
+        for field in RECORD_FIELDS {
+            if let Some(v) = object.remove(field) {
+                known.insert(field.to_string(), v);
+            }
+        }
+        // Fields with unexpected types are kept as metrics or extra data, rather than dropped
+        let mut record = serde_json::from_value::<Self>(Value::Object(known.clone()))
+            .unwrap_or_else(|_| {
+                object.extend(known);
+                Self::default()
+            });
+        // A "metrics" field which could not be parsed as such must not end up
+        // in `extra` under the same name, since it would clash with `metrics`.
+        match object.remove("metrics") {
+            Some(Value::Object(metrics)) => {
+                for (key, v) in metrics {
+                    match v.as_f64() {
+                        Some(number) => { record.metrics.insert(key, number); },
+                        None => { record.extra.insert(format!("metrics.{key}"), v); }
+                    }
+                }
+            },
+            Some(other) => { record.extra.insert("legacy_metrics".to_string(), other); },
+            None => {}
+        }
+        for (key, v) in object {
+            match v.as_f64() {
+                Some(number) if !is_typed => { record.metrics.insert(key, number); },
+                _ => { record.extra.insert(key, v); }
+            }
+        }
+        record
+    }
+
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).expect("PerformanceRecord is always serializable")
+    }
+}
+
+/// Summary of a single metric across folds.
+#[allow(unused)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct MetricSummary {
+    pub count: usize,
+    pub mean: f64,
+    /// Sample standard deviation; `None` if there is only a single value.
+    pub std: Option<f64>,
+    pub min: f64,
+    pub max: f64
+}
+
+impl MetricSummary {
+    pub(crate) fn from_values(values: &[f64]) -> Self {
+        let count = values.len();
+        let mean = values.iter().sum::<f64>() / count as f64;
+        let std = if count < 2 {
+            None
+        } else {
+            let squares = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>();
+            Some((squares / (count - 1) as f64).sqrt())
+        };
+        Self{
+            count,
+            mean,
+            std,
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+        }
+    }
+}
+
+/// Summarise every metric across folds. Only records for the given
+/// split are considered (if any). When a fold has records for
+/// multiple epochs, only the last epoch is used.
+pub fn aggregate_records(records: &[PerformanceRecord],
+                         split: Option<&str>) -> BTreeMap<String, MetricSummary> {
+    let mut values: BTreeMap<&String, Vec<f64>> = BTreeMap::new();
+    for record in final_records(records, split) {
+        for (metric, value) in record.metrics.iter() {
+            values.entry(metric).or_default().push(*value);
+        }
+    }
+    values.into_iter()
+        .map(|(metric, v)| (metric.clone(), MetricSummary::from_values(&v)))
+        .collect()
+}
+
+/// The record of the last epoch of every fold for the given split (if any),
+/// ordered by fold. Without a split, folds of different splits are kept apart,
+/// and ordered by split within every fold. Records without a fold are
+/// considered to be folds of their own, and come last.
+pub(crate) fn final_records<'a>(records: &'a [PerformanceRecord],
+                                split: Option<&str>) -> Vec<&'a PerformanceRecord> {
+    let mut last_per_fold: BTreeMap<(u32, Option<&str>), &PerformanceRecord> = BTreeMap::new();
+    let mut unfolded = Vec::new();
+    for record in records {
+        if split.is_some_and(|s| record.split.as_deref() != Some(s)) {
+            continue;
+        }
+        match record.fold {
+            None => unfolded.push(record),
+            Some(fold) => {
+                let key = (fold, record.split.as_deref());
+                let entry = last_per_fold.entry(key).or_insert(record);
+                if record.epoch > entry.epoch {
+                    *entry = record;
+                }
+            }
+        }
+    }
+    last_per_fold.into_values().chain(unfolded).collect()
+}
+
+/// Hex encoded SHA-256 hash of a model config. Keys are sorted,
+/// so the hash does not depend on the order of the keys.
+pub fn config_hash(config: &HashMap<String, Value>) -> String {
+    let sorted = config.iter().collect::<BTreeMap<_, _>>();
+    let canonical = serde_json::to_vec(&sorted).expect("Config is always serializable");
+    hex::encode(Sha256::digest(canonical))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(fold: u32, split: &str, epoch: u32, accuracy: f64) -> PerformanceRecord {
+        PerformanceRecord{
+            fold: Some(fold),
+            split: Some(split.to_string()),
+            epoch: Some(epoch),
+            ..PerformanceRecord::new(BTreeMap::from([("accuracy".to_string(), accuracy)]))
+        }
+    }
+
+    #[test]
+    fn from_json_typed() {
+        let value = json!({
+            "fold": 2,
+            "split": "test",
+            "metrics": {"accuracy": 0.9},
+            "seed": 42
+        });
+        let record = PerformanceRecord::from_json(value.clone());
+        assert_eq!(record.fold, Some(2));
+        assert_eq!(record.split.as_deref(), Some("test"));
+        assert_eq!(record.metrics, BTreeMap::from([("accuracy".to_string(), 0.9)]));
+        assert_eq!(record.extra.get("seed"), Some(&json!(42)));
+        assert_eq!(record.to_json(), value);
+    }
+
+    #[test]
+    fn from_json_legacy() {
+        let record = PerformanceRecord::from_json(json!({
+            "fold": 1,
+            "accuracy": 0.8,
+            "f1": 0.7,
+            "model": "cnn"
+        }));
+        assert_eq!(record.fold, Some(1));
+        assert_eq!(record.metrics.len(), 2);
+        assert_eq!(record.metrics["accuracy"], 0.8);
+        assert_eq!(record.metrics["f1"], 0.7);
+        assert_eq!(record.extra.get("model"), Some(&json!("cnn")));
+    }
+
+    #[test]
+    fn from_json_legacy_invalid_fields() {
+        let record = PerformanceRecord::from_json(json!({"fold": "first", "metrics": [1, 2]}));
+        assert_eq!(record.fold, None);
+        assert_eq!(record.extra.get("fold"), Some(&json!("first")));
+        assert_eq!(record.extra.get("legacy_metrics"), Some(&json!([1, 2])));
+        let record = PerformanceRecord::from_json(json!([0.5]));
+        assert_eq!(record.extra.get("data"), Some(&json!([0.5])));
+    }
+
+    #[test]
+    fn final_records_uses_last_epoch() {
+        let records = vec![
+            record(1, "test", 2, 0.6),
+            record(0, "test", 1, 0.5),
+            record(1, "test", 1, 0.4),
+            record(0, "test", 3, 0.7)
+        ];
+        let finals = final_records(&records, None);
+        assert_eq!(finals, vec![&records[3], &records[0]]);
+    }
+
+    #[test]
+    fn final_records_keeps_splits_apart() {
+        let records = vec![
+            record(0, "train", 1, 0.9),
+            record(0, "test", 1, 0.5),
+            record(1, "train", 1, 0.8),
+            record(1, "test", 1, 0.6),
+            PerformanceRecord::new(BTreeMap::new())
+        ];
+        assert_eq!(final_records(&records, None).len(), 5);
+        assert_eq!(final_records(&records, Some("test")), vec![&records[1], &records[3]]);
+    }
+
+    #[test]
+    fn aggregate_per_split() {
+        let records = vec![
+            record(0, "train", 1, 0.9),
+            record(0, "test", 1, 0.5),
+            record(1, "train", 1, 0.7),
+            record(1, "test", 1, 0.7)
+        ];
+        let summary = aggregate_records(&records, Some("test"))["accuracy"];
+        assert_eq!(summary.count, 2);
+        assert!((summary.mean - 0.6).abs() < 1e-12);
+        assert!((summary.std.unwrap() - 0.02_f64.sqrt()).abs() < 1e-12);
+        assert_eq!(summary.min, 0.5);
+        assert_eq!(summary.max, 0.7);
+        let summary = aggregate_records(&records, None)["accuracy"];
+        assert_eq!(summary.count, 4);
+        assert!((summary.mean - 0.7).abs() < 1e-12);
+    }
+
+    #[test]
+    fn aggregate_single_value_has_no_std() {
+        let summary = aggregate_records(&[record(0, "test", 1, 0.5)], None)["accuracy"];
+        assert_eq!(summary.count, 1);
+        assert_eq!(summary.std, None);
+    }
+}
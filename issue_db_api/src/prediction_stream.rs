@@ -0,0 +1,216 @@
+use std::fmt::Formatter;
+use std::io::Read;
+use std::sync::mpsc::{Receiver, SyncSender};
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde_json::Value;
+use crate::errors::{APIError, APIResult};
+use crate::predictions::Prediction;
+
+/// Number of parsed predictions which may be buffered before
+/// the parsing thread waits for the consumer to catch up.
+const STREAM_BUFFER_SIZE: usize = 1024;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Download
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Iterator over the predictions in a `{"predictions": {issue_id: prediction, ...}}`
+/// response, which are parsed while the response is being received.
+/// At most `STREAM_BUFFER_SIZE` predictions are held in memory at any time.
+///
+/// Dropping the stream before it is exhausted aborts the download.
+#[allow(unused)]
+#[derive(Debug)]
+pub struct PredictionStream {
+    receiver: Receiver<APIResult<(String, Value)>>
+}
+
+#[allow(unused)]
+impl PredictionStream {
+    /// Parse the body in a background thread. `on_finish` is called with
+    /// the number of bytes read and whether parsing succeeded.
+    pub(crate) fn spawn<R, F>(body: R, on_finish: F) -> Self
+    where
+        R: Read + Send + 'static,
+        F: FnOnce(u64, bool) + Send + 'static
+    {
+        let (sender, receiver) = std::sync::mpsc::sync_channel(STREAM_BUFFER_SIZE);
+        std::thread::spawn(move || {
+            let mut body = CountingReader{inner: body, count: 0};
+            let mut deserializer = serde_json::Deserializer::from_reader(&mut body);
+            let outcome = deserializer
+                .deserialize_map(ResponseVisitor{sender: &sender})
+                .and_then(|_| deserializer.end());
+            let succeeded = outcome.is_ok();
+            // Sending fails if the stream was dropped, in which case
+            // nobody is interested in the error anymore.
+            if let Err(e) = outcome {
+                let _ = sender.send(Err(APIError::from(e)));
+            }
+            on_finish(body.count, succeeded);
+        });
+        Self{receiver}
+    }
+
+    /// Parse and validate every prediction.
+    pub fn typed(self) -> impl Iterator<Item=APIResult<(String, Prediction)>> + Send {
+        self.map(|entry| {
+            let (issue, raw) = entry?;
+            Ok((issue, Prediction::from_json(raw)?))
+        })
+    }
+}
+
+impl Iterator for PredictionStream {
+    type Item = APIResult<(String, Value)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.recv().ok()
+    }
+}
+
+struct CountingReader<R> {
+    inner: R,
+    count: u64
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+}
+
+/// Visits the top level response object, forwarding the
+/// entries of the `predictions` field.
+struct ResponseVisitor<'a> {
+    sender: &'a SyncSender<APIResult<(String, Value)>>
+}
+
+impl<'de> Visitor<'de> for ResponseVisitor<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("a predictions response")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "predictions" {
+                map.next_value_seed(EntriesSeed{sender: self.sender})?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sends every entry of the predictions object as soon as it has been parsed.
+struct EntriesSeed<'a> {
+    sender: &'a SyncSender<APIResult<(String, Value)>>
+}
+
+impl<'de> DeserializeSeed<'de> for EntriesSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for EntriesSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("a mapping of issue IDs to predictions")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(entry) = map.next_entry::<String, Value>()? {
+            if self.sender.send(Ok(entry)).is_err() {
+                return Err(serde::de::Error::custom("prediction stream was closed"));
+            }
+        }
+        Ok(())
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+//////////////////////////////////////////////////////////////////////////////////////////////////
+// Upload
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum UploadState {
+    Start,
+    First,
+    Rest,
+    Done
+}
+
+/// Reader producing `{"predictions": {issue_id: prediction, ...}}`,
+/// serializing a single prediction at a time.
+pub(crate) struct PredictionUpload<I> {
+    entries: I,
+    buffer: Vec<u8>,
+    position: usize,
+    state: UploadState
+}
+
+impl<I> PredictionUpload<I>
+where
+    I: Iterator<Item=(String, Value)>
+{
+    pub(crate) fn new(entries: I) -> Self {
+        Self{entries, buffer: Vec::new(), position: 0, state: UploadState::Start}
+    }
+
+    fn fill_buffer(&mut self) -> std::io::Result<()> {
+        self.buffer.clear();
+        self.position = 0;
+        match self.state {
+            UploadState::Start => {
+                self.buffer.extend_from_slice(b"{\"predictions\":{");
+                self.state = UploadState::First;
+            },
+            UploadState::First | UploadState::Rest => match self.entries.next() {
+                Some((issue, prediction)) => {
+                    if self.state == UploadState::Rest {
+                        self.buffer.push(b',');
+                    }
+                    serde_json::to_writer(&mut self.buffer, &issue)?;
+                    self.buffer.push(b':');
+                    serde_json::to_writer(&mut self.buffer, &prediction)?;
+                    self.state = UploadState::Rest;
+                },
+                None => {
+                    self.buffer.extend_from_slice(b"}}");
+                    self.state = UploadState::Done;
+                }
+            },
+            UploadState::Done => {}
+        }
+        Ok(())
+    }
+}
+
+impl<I> Read for PredictionUpload<I>
+where
+    I: Iterator<Item=(String, Value)>
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.position == self.buffer.len() {
+            if self.state == UploadState::Done {
+                return Ok(0);
+            }
+            self.fill_buffer()?;
+        }
+        let n = buf.len().min(self.buffer.len() - self.position);
+        buf[..n].copy_from_slice(&self.buffer[self.position..self.position + n]);
+        self.position += n;
+        Ok(n)
+    }
+}
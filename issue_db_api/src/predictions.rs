@@ -0,0 +1,94 @@
+use serde_json::Value;
+use crate::errors::{APIError, APIResult};
+use crate::labels::Label;
+
+/// Prediction for a single class of the architectural label.
+#[allow(unused)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ClassPrediction {
+    #[serde(rename = "prediction")] pub predicted: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub probability: Option<f64>
+}
+
+#[allow(unused)]
+impl ClassPrediction {
+    pub fn new(predicted: bool, probability: Option<f64>) -> Self {
+        Self{predicted, probability}
+    }
+}
+
+/// Prediction of a model version for a single issue.
+/// Mirrors `Label`, with an optional probability per class
+/// and an optional overall confidence.
+///
+/// Stored on the server as
+/// `{"existence": {"prediction": bool, "probability": float}, ..., "confidence": float}`.
+#[allow(unused)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Prediction {
+    pub existence: ClassPrediction,
+    pub executive: ClassPrediction,
+    pub property: ClassPrediction,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub confidence: Option<f64>
+}
+
+fn check_probability(name: &str, value: Option<f64>) -> APIResult<()> {
+    match value {
+        Some(p) if !(0.0..=1.0).contains(&p) => {
+            let msg = format!("Invalid prediction: {name} must be between 0 and 1, got {p}");
+            Err(APIError::ValidationError(msg))
+        },
+        _ => Ok(())
+    }
+}
+
+#[allow(unused)]
+impl Prediction {
+    pub fn new(existence: ClassPrediction,
+               executive: ClassPrediction,
+               property: ClassPrediction,
+               confidence: Option<f64>) -> Self {
+        Self{existence, executive, property, confidence}
+    }
+
+    /// A prediction without probabilities or confidence.
+    pub fn from_label(label: Label) -> Self {
+        Self{
+            existence: ClassPrediction::new(label.existence(), None),
+            executive: ClassPrediction::new(label.executive(), None),
+            property: ClassPrediction::new(label.property(), None),
+            confidence: None
+        }
+    }
+
+    /// The predicted label.
+    pub fn label(&self) -> Label {
+        Label::new(self.existence.predicted, self.executive.predicted, self.property.predicted)
+    }
+
+    pub fn non_architectural(&self) -> bool {
+        !(self.existence.predicted || self.executive.predicted || self.property.predicted)
+    }
+
+    pub fn validate(&self) -> APIResult<()> {
+        check_probability("existence probability", self.existence.probability)?;
+        check_probability("executive probability", self.executive.probability)?;
+        check_probability("property probability", self.property.probability)?;
+        check_probability("confidence", self.confidence)
+    }
+
+    /// Parse and validate a raw prediction, as returned by the server.
+    pub fn from_json(value: Value) -> APIResult<Self> {
+        let prediction: Self = serde_json::from_value(value)
+            .map_err(|e| APIError::ValidationError(format!("Invalid prediction: {e}")))?;
+        prediction.validate()?;
+        Ok(prediction)
+    }
+
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).expect("Prediction is always serializable")
+    }
+}
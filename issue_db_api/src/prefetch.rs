@@ -0,0 +1,56 @@
+use std::sync::{Arc, Mutex, Weak};
+use crate::api_core::{IssueAPI, IssueData};
+use crate::config::IssueAttribute;
+use crate::errors::APIResult;
+use crate::util::CacheLockError;
+
+/// A group of issues which were loaded together.
+/// When an attribute which was not loaded up front is requested
+/// for one member of the group, it is fetched for all members
+/// of the group which are still alive, using a single batched request.
+#[allow(unused)]
+#[derive(Debug, Default)]
+pub(crate) struct LoadingGroup {
+    members: Mutex<Vec<Weak<IssueData>>>
+}
+
+#[allow(unused)]
+impl LoadingGroup {
+    pub(crate) fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub(crate) fn register(self: &Arc<Self>, data: &Arc<IssueData>) {
+        data.join_group(self.clone());
+        if let Ok(mut members) = self.members.lock() {
+            members.push(Arc::downgrade(data));
+        }
+    }
+
+    pub(crate) fn load_attribute(&self,
+                                 api: &IssueAPI,
+                                 attribute: IssueAttribute) -> APIResult<()> {
+        let pending = {
+            let mut members = self.members
+                .lock()
+                .map_err(|_| CacheLockError{})?;
+            members.retain(|m| m.strong_count() > 0);
+            members.iter()
+                .filter_map(Weak::upgrade)
+                .filter(|d| !d.is_loaded(attribute))
+                .collect::<Vec<_>>()
+        };
+        if pending.is_empty() {
+            return Ok(());
+        }
+        tracing::debug!(%attribute, issues = pending.len(), "prefetching attribute for loading group");
+        let ids = pending.iter().map(|d| d.ident().clone()).collect();
+        let mut fetched = api.get_issue_data(ids, vec![attribute])?;
+        for data in pending {
+            if let Some(update) = fetched.remove(data.ident()) {
+                data.update(update)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use serde_json::{Map, Value};
+use crate::errors::{APIError, APIResult};
+use crate::models::ModelVersion;
+use crate::query::Query;
+
+/// Separates the human-readable part of a version description
+/// from the metadata (provenance and checksum) stored after it.
+const PROVENANCE_MARKER: &str = "\n\n-----BEGIN PROVENANCE-----\n";
+
+const CHECKSUM_FIELD: &str = "sha256";
+
+const PROVENANCE_FIELDS: [&str; 5] = ["embedding_ids", "training_query", "snapshot_hash", "code_commit", "file_ids"];
+
+/// Where a model version came from: what it was trained on,
+/// and what it depends on.
+#[allow(unused)]
+#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Provenance {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] pub embedding_ids: Vec<String>,
+    /// JSON representation of the query selecting the training issues.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub training_query: Option<Value>,
+    /// Hash of the dataset snapshot used for training.
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub snapshot_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub code_commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] pub file_ids: Vec<String>
+}
+
+#[allow(unused)]
+impl Provenance {
+    pub fn with_training_query(mut self, query: Query) -> Self {
+        self.training_query = Some(query.into_json());
+        self
+    }
+
+    pub fn uses_embedding(&self, embedding_id: &str) -> bool {
+        self.embedding_ids.iter().any(|e| e == embedding_id)
+    }
+
+    pub fn uses_file(&self, file_id: &str) -> bool {
+        self.file_ids.iter().any(|f| f == file_id)
+    }
+
+    /// Parse provenance given by a user. Unknown fields are rejected, so typos
+    /// are not silently dropped; stored provenance is parsed leniently.
+    pub fn from_json(value: Value) -> APIResult<Self> {
+        if let Some(key) = value.as_object()
+            .and_then(|o| o.keys().find(|k| !PROVENANCE_FIELDS.contains(&k.as_str()))) {
+            return Err(APIError::ValidationError(format!("Invalid provenance: unknown field \"{key}\"")));
+        }
+        serde_json::from_value(value)
+            .map_err(|e| APIError::ValidationError(format!("Invalid provenance: {e}")))
+    }
+
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).expect("Provenance is always serializable")
+    }
+}
+
+/// Versions found by a provenance query, together with the models
+/// whose versions could not be retrieved.
+#[allow(unused)]
+#[derive(Debug, Default)]
+pub struct ProvenanceMatches {
+    pub versions: Vec<ModelVersion>,
+    /// Model ID and the error raised while listing its versions.
+    pub failed: Vec<(String, APIError)>
+}
+
+#[allow(unused)]
+impl ProvenanceMatches {
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Metadata stored along with the description of a version.
+/// Stored as the provenance fields, and the checksum under `sha256`.
+#[allow(unused)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct VersionMetadata {
+    pub(crate) provenance: Option<Provenance>,
+    /// Hex encoded SHA-256 checksum of the uploaded model binary.
+    pub(crate) sha256: Option<String>
+}
+
+/// Split a stored version description into its text and metadata.
+/// Descriptions without (valid) metadata are returned unchanged.
+pub(crate) fn split_description(stored: String) -> (String, VersionMetadata) {
+    if let Some((text, raw)) = stored.split_once(PROVENANCE_MARKER) {
+        if let Ok(Value::Object(mut object)) = serde_json::from_str::<Value>(raw) {
+            let sha256 = match object.remove(CHECKSUM_FIELD) {
+                Some(Value::String(checksum)) => Some(checksum),
+                Some(_) => return (stored, VersionMetadata::default()),
+                None => None
+            };
+            // Only the checksum was stored
+            if sha256.is_some() && object.is_empty() {
+                return (text.to_string(), VersionMetadata{provenance: None, sha256});
+            }
+            if let Ok(provenance) = serde_json::from_value::<Provenance>(Value::Object(object)) {
+                let metadata = VersionMetadata{provenance: Some(provenance), sha256};
+                return (text.to_string(), metadata);
+            }
+        }
+    }
+    (stored, VersionMetadata::default())
+}
+
+pub(crate) fn join_description(text: &str, metadata: &VersionMetadata) -> String {
+    if metadata.provenance.is_none() && metadata.sha256.is_none() {
+        return text.to_string();
+    }
+    let mut object = match metadata.provenance.as_ref().map(Provenance::to_json) {
+        Some(Value::Object(object)) => object,
+        _ => Map::new()
+    };
+    if let Some(ref checksum) = metadata.sha256 {
+        object.insert(CHECKSUM_FIELD.to_string(), Value::String(checksum.clone()));
+    }
+    format!("{text}{PROVENANCE_MARKER}{}", Value::Object(object))
+}
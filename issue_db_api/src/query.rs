@@ -6,6 +6,7 @@ use serde_json::{Map, Value};
 pub enum Query {
     Tag(QueryCMP, String),
     Project(String),
+    Author(String),
     Identifier(String),
     Key(String),
     And(Vec<Query>),
@@ -37,6 +38,9 @@ impl Query {
             Query::Project(project) => {
                 Query::Tag(QueryCMP::Eq, project).into_json()
             }
+            Query::Author(author) => {
+                Query::Tag(QueryCMP::Eq, author).into_json()
+            }
             Query::Identifier(ident) => {
                 let mut map = Map::new();
                 map.insert("_id".to_string(), Value::String(ident));
@@ -85,6 +89,7 @@ impl Display for Query {
                 QueryCMP::Ne => write!(f, "{{\"tags\": {{\"$ne\": \"{}\"}}}}", name)
             },
             Query::Project(project) => write!(f, "{{\"tags\": {{\"$eq\": \"{}\"}}}}", project),
+            Query::Author(author) => write!(f, "{{\"tags\": {{\"$eq\": \"{}\"}}}}", author),
             Query::Identifier(ident) => write!(f, "{{\"_id\": {{\"$eq\": \"{}\"}}}}", ident),
             Query::Key(key) => write!(f, "{{\"key\": {{\"$eq\": \"{}\"}}}}", key),
             Query::And(arms)=> {
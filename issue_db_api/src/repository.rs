@@ -3,23 +3,39 @@ use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use serde_json::Value;
 
+use crate::agreement::{compute_agreement, AgreementReport};
 use crate::api_core::IssueAPI;
-use crate::config::{CachingPolicy, IssueLoadingSettings, ConfigHandlingPolicy};
+use crate::config::{CachingPolicy, IssueAttribute, IssueLoadingSettings, ConfigHandlingPolicy, PrefetchPolicy};
+use crate::comments::{Comment, CommentThread};
+use crate::comment_search::CommentFilter;
 use crate::embedding::Embedding;
-use crate::issues::Issue;
-use crate::models::Model;
+use crate::issues::{Issue, IssueSnapshot};
+use crate::labels::Label;
+use crate::models::{Model, ModelVersion, TestRun};
+use crate::predictions::Prediction;
 use crate::query::Query;
-use crate::tags::Tag;
-use crate::errors::APIResult;
+use crate::review::{Review, ReviewOutcome};
+use crate::tags::{apply_tag_diffs, BulkTagReport, Tag, TagDiff, TagType};
+use crate::errors::{APIError, APIResult};
 use crate::files::File;
+use crate::label_schemas::LabelSchema;
+use crate::model_configs::ConfigSchema;
+use crate::provenance::{Provenance, ProvenanceMatches};
+use crate::labelling::LabellingSession;
+use crate::metrics::RequestStats;
 use crate::projects::Project;
+use crate::sessions::{register_session, Session};
 
+/// Number of issues for which comments are requested at once
+/// when searching comments client-side.
+const COMMENT_BATCH_SIZE: usize = 500;
 
 #[allow(unused)]
 #[derive(Debug)]
 pub struct IssueRepository {
     label_caching: CachingPolicy,
     config_handling: ConfigHandlingPolicy,
+    prefetching: PrefetchPolicy,
     api: Arc<IssueAPI>
 }
 
@@ -30,13 +46,8 @@ impl IssueRepository {
                          label_caching_policy: CachingPolicy,
                          config_handling_policy: ConfigHandlingPolicy,
                          allow_self_signed_certs: bool) -> APIResult<Self> {
-        Ok(
-            Self{
-                api: Arc::new(IssueAPI::new_read_only(url, allow_self_signed_certs)?),
-                label_caching: label_caching_policy,
-                config_handling: config_handling_policy
-            }
-        )
+        let api = Arc::new(IssueAPI::new_read_only(url, allow_self_signed_certs)?);
+        Ok(Self::from_api(api, label_caching_policy, config_handling_policy))
     }
 
     pub fn new(url: String,
@@ -46,13 +57,7 @@ impl IssueRepository {
                config_handling_policy: ConfigHandlingPolicy,
                allow_self_signed_certs: bool) -> APIResult<Self> {
         let api = Arc::new(IssueAPI::new(url, username, password, allow_self_signed_certs)?);
-        Ok(
-            Self{
-                api,
-                label_caching: label_caching_policy,
-                config_handling: config_handling_policy
-            }
-        )
+        Ok(Self::from_api(api, label_caching_policy, config_handling_policy))
     }
     
     pub fn new_with_token(url: String,
@@ -61,13 +66,37 @@ impl IssueRepository {
                           config_handling_policy: ConfigHandlingPolicy,
                           allow_self_signed_certs: bool) -> APIResult<Self> {
         let api = Arc::new(IssueAPI::with_token(url, token, allow_self_signed_certs)?);
-        Ok(
-            Self{
-                api,
-                label_caching: label_caching_policy,
-                config_handling: config_handling_policy
-            }
-        )
+        Ok(Self::from_api(api, label_caching_policy, config_handling_policy))
+    }
+
+    fn from_api(api: Arc<IssueAPI>,
+                label_caching: CachingPolicy,
+                config_handling: ConfigHandlingPolicy) -> Self {
+        let prefetching = PrefetchPolicy::default();
+        register_session(&api, label_caching, prefetching);
+        Self{api, label_caching, config_handling, prefetching}
+    }
+
+    /// Set the prefetch policy for issues loaded through this repository.
+    /// Issues are loaded without prefetching by default.
+    pub fn with_prefetch_policy(mut self, prefetch_policy: PrefetchPolicy) -> Self {
+        self.prefetching = prefetch_policy;
+        register_session(&self.api, self.label_caching, self.prefetching);
+        self
+    }
+
+    pub fn url(&self) -> String {
+        self.api.url().clone()
+    }
+
+    /// Restore an issue from a snapshot, binding it to this repository.
+    pub fn restore_issue(&self, snapshot: IssueSnapshot) -> Issue {
+        let session = Session{
+            api: self.api.clone(),
+            label_caching: self.label_caching,
+            prefetching: self.prefetching
+        };
+        Issue::from_snapshot(session, snapshot)
     }
 
     pub fn search(&self,
@@ -77,9 +106,9 @@ impl IssueRepository {
         if ids.is_empty() {
             return Ok(Vec::new());
         }
-        issue_loading_settings.load_issues(self.api.clone(),
-                                           ids,
-                                           self.label_caching)
+        issue_loading_settings
+            .with_default_prefetch_policy(self.prefetching)
+            .load_issues(self.api.clone(), ids, self.label_caching)
     }
 
     pub fn find_issue_by_key(&self,
@@ -87,14 +116,219 @@ impl IssueRepository {
                              name: String,
                              loading: IssueLoadingSettings) -> APIResult<Issue> {
         let id = self.api.find_issue_id_by_key(project, name)?;
-        loading.load_issue(self.api.clone(), id, self.label_caching)
+        loading.with_default_prefetch_policy(self.prefetching)
+            .load_issue(self.api.clone(), id, self.label_caching)
     }
 
     pub fn find_issues_by_key(&self,
                               issues: Vec<(String, String)>,
                               loading: IssueLoadingSettings) -> APIResult<Vec<Issue>> {
         let ids = self.api.find_issue_ids_by_keys(issues)?;
-        loading.load_issues(self.api.clone(), ids, self.label_caching)
+        loading.with_default_prefetch_policy(self.prefetching)
+            .load_issues(self.api.clone(), ids, self.label_caching)
+    }
+
+    /// Start a labelling session with at most `batch_size` unlabelled
+    /// issues matching `query` (or any unlabelled issues, if `query` is `None`).
+    pub fn start_labelling_session(&self,
+                                   query: Option<Query>,
+                                   batch_size: usize,
+                                   loading: IssueLoadingSettings) -> APIResult<LabellingSession> {
+        LabellingSession::start(
+            self.api.clone(), query, batch_size, loading, self.label_caching, self.prefetching
+        )
+    }
+
+    pub fn issues_in_review(&self) -> APIResult<Vec<Review>> {
+        let reviews = self.api.get_issues_in_review()?
+            .into_iter()
+            .map(|r| r.into_bound_review(self.api.clone(), self.label_caching, self.prefetching))
+            .collect();
+        Ok(reviews)
+    }
+
+    pub fn bulk_start_review<'a, I>(&self, issues: I, reason: Option<String>) -> APIResult<()>
+    where
+        I: IntoIterator<Item = &'a Issue>
+    {
+        let ids = issues.into_iter().map(|i| i.ident().clone()).collect();
+        self.api.bulk_start_review(ids, reason)
+    }
+
+    // Issues are hashed by their ID only, which never changes.
+    #[allow(clippy::mutable_key_type)]
+    pub fn bulk_finish_review(&self, outcomes: HashMap<&Issue, ReviewOutcome>) -> APIResult<()> {
+        let mut payload = HashMap::with_capacity(outcomes.len());
+        for (issue, outcome) in outcomes {
+            payload.insert(issue.ident().clone(), outcome.into_payload(&self.api)?);
+        }
+        self.api.bulk_finish_review(payload)
+    }
+
+    /// Labelling comments of all issues matching `query`, keyed by issue ID.
+    pub fn labelling_comments(&self, query: Query) -> APIResult<HashMap<String, Vec<Comment>>> {
+        let ids = self.api.search(query)?;
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let comments = self.api.get_labeling_comments_for_issues(ids)?
+            .into_iter()
+            .map(|(issue_id, comments)| {
+                let bound = comments.into_iter()
+                    .map(|c| c.into_comment(self.api.clone(), issue_id.clone()))
+                    .collect();
+                (issue_id, bound)
+            })
+            .collect();
+        Ok(comments)
+    }
+
+    /// Find all labelling comments matching `filter`, on issues matching `query`.
+    /// The server-side comment search is used if the server supports it;
+    /// otherwise, comments are fetched in batches and filtered locally.
+    pub fn search_labelling_comments(&self,
+                                     query: Query,
+                                     filter: &CommentFilter,
+                                     loading: IssueLoadingSettings) -> APIResult<Vec<(Issue, Comment)>> {
+        let matches = self.matching_labelling_comments(query, filter)?;
+        if matches.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut ids = matches.iter().map(|c| c.issue_id().clone()).collect::<Vec<_>>();
+        ids.sort();
+        ids.dedup();
+        let issues = loading
+            .with_default_prefetch_policy(self.prefetching)
+            .load_issues(self.api.clone(), ids, self.label_caching)?
+            .into_iter()
+            .map(|i| (i.ident().clone(), i))
+            .collect::<HashMap<_, _>>();
+        let mut pairs = Vec::with_capacity(matches.len());
+        for comment in matches {
+            if let Some(issue) = issues.get(comment.issue_id()) {
+                pairs.push((issue.share()?, comment));
+            }
+        }
+        Ok(pairs)
+    }
+
+    /// All labelling comments matching `filter`, on issues matching `query`.
+    /// Without server-side search, comments are fetched in batches,
+    /// which in turn falls back to one request per issue on servers
+    /// which cannot return comments for many issues at once.
+    fn matching_labelling_comments(&self, query: Query, filter: &CommentFilter) -> APIResult<Vec<Comment>> {
+        let comments = match self.api.search_labeling_comments(query.clone(), filter.to_json())? {
+            Some(comments) => comments,
+            None => {
+                let ids = self.api.search(query)?;
+                let mut comments = HashMap::with_capacity(ids.len());
+                for chunk in ids.chunks(COMMENT_BATCH_SIZE) {
+                    comments.extend(self.api.get_labeling_comments_for_issues(chunk.to_vec())?);
+                }
+                comments
+            }
+        };
+        let matches = comments.into_iter()
+            .flat_map(|(issue_id, comments)| {
+                comments.into_iter()
+                    .map(move |c| c.into_comment(self.api.clone(), issue_id.clone()))
+            })
+            .filter(|c| filter.is_match(c))
+            .collect();
+        Ok(matches)
+    }
+
+    /// Write the comment threads of all issues matching `query`
+    /// to a JSON file, as a mapping from issue IDs to lists of threads.
+    pub fn export_labelling_comments(&self, query: Query, path: String) -> APIResult<()> {
+        let export = self.labelling_comments(query)?
+            .into_iter()
+            .map(|(issue_id, comments)| {
+                let threads = CommentThread::build(comments)
+                    .iter()
+                    .map(CommentThread::to_record)
+                    .collect::<Vec<_>>();
+                (issue_id, threads)
+            })
+            .collect::<HashMap<_, _>>();
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), &export)?;
+        Ok(())
+    }
+
+    /// Compute inter-annotator agreement for every field of the given
+    /// label schema, over all issues matching `query`.
+    pub fn label_agreement(&self, query: Query, schema: &str) -> APIResult<Vec<AgreementReport>> {
+        let schema = self.api.label_schemas().get(schema)?;
+        let ids = self.api.search(query)?;
+        if ids.is_empty() {
+            return compute_agreement(&schema, &HashMap::new());
+        }
+        let labels = self.api.get_annotator_labels(ids)?;
+        compute_agreement(&schema, &labels)
+    }
+
+    /// The predictions of `version` for all issues matching `query`,
+    /// together with the issues and their manual labels.
+    /// Issues without a prediction are left out.
+    pub fn predictions_with_labels(&self,
+                                   version: &ModelVersion,
+                                   query: Query,
+                                   loading: IssueLoadingSettings) -> APIResult<Vec<(Issue, Prediction, Option<Label>)>> {
+        let ids = self.api.search(query)?;
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut predictions = version.get_typed_predictions(Some(ids.clone()))?;
+        let ids = ids.into_iter()
+            .filter(|id| predictions.contains_key(id))
+            .collect::<Vec<_>>();
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let issues = loading
+            .with_preloaded_labels()
+            .with_default_prefetch_policy(self.prefetching)
+            .load_issues(self.api.clone(), ids, self.label_caching)?;
+        issues.into_iter()
+            .map(|issue| {
+                let prediction = predictions.remove(issue.ident())
+                    .expect("Loaded issue without prediction");
+                // Labels were preloaded, so this does not send a request
+                let label = issue.get_manual_label()?;
+                Ok((issue, prediction, label))
+            })
+            .collect()
+    }
+
+    /// Load the given attributes for all issues which do not have
+    /// them loaded yet, using a single (batched) request.
+    pub fn prefetch<'a, I>(&self, issues: I, attributes: Vec<IssueAttribute>) -> APIResult<()>
+    where
+        I: IntoIterator<Item = &'a Issue>
+    {
+        let missing = issues
+            .into_iter()
+            .filter(|i| attributes.iter().any(|a| !i.data().is_loaded(*a)))
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let ids = missing.iter().map(|i| i.ident().clone()).collect();
+        let mut data = self.api.get_issue_data(ids, attributes)?;
+        for issue in missing {
+            if let Some(fetched) = data.remove(issue.ident()) {
+                issue.data().update(fetched)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Configure how `issue-data` requests are split up.
+    /// Issues are requested in batches of at most `batch_size` issues,
+    /// with up to `workers` batches being requested concurrently.
+    pub fn set_issue_data_batching(&self, batch_size: usize, workers: usize) -> APIResult<()> {
+        self.api.set_issue_data_batching(batch_size, workers)
     }
 
     pub fn projects(&self) -> APIResult<Vec<Project>> {
@@ -130,20 +364,99 @@ impl IssueRepository {
     pub fn tags(&self) -> APIResult<Vec<Tag>> {
         let tags = self.api.get_all_tags()?
             .into_iter()
-            .map(|t| t.into_bound_tag(self.api.clone()))
+            .map(|t| t.into_bound_tag(self.api.clone(), self.label_caching, self.prefetching))
+            .collect();
+        Ok(tags)
+    }
+
+    pub fn get_tag(&self, name: String) -> APIResult<Tag> {
+        let tag = self.api
+            .get_tag_info(name)?
+            .into_bound_tag(self.api.clone(), self.label_caching, self.prefetching);
+        Ok(tag)
+    }
+
+    pub fn tags_by_type(&self, tag_type: TagType) -> APIResult<Vec<Tag>> {
+        let tags = self.tags()?
+            .into_iter()
+            .filter(|t| t.tag_type() == &tag_type)
             .collect();
         Ok(tags)
     }
 
     pub fn add_new_tag(&self, name: String, description: String) -> APIResult<()> {
-        self.api.register_new_tag(name, description)
+        self.api.register_new_tag(name, description, None)
+    }
+
+    pub fn add_new_tag_of_type(&self,
+                               name: String,
+                               description: String,
+                               tag_type: TagType) -> APIResult<()> {
+        self.api.register_new_tag(name, description, Some(tag_type))
+    }
+
+    /// Names of all authors, according to the author tags.
+    pub fn authors(&self) -> APIResult<Vec<String>> {
+        let names = self.tags_by_type(TagType::Author)?
+            .into_iter()
+            .map(|t| t.name().clone())
+            .collect();
+        Ok(names)
+    }
+
+    /// All issues authored by `author`, based on the author tag with that name.
+    pub fn issues_by_author(&self,
+                            author: String,
+                            loading: IssueLoadingSettings) -> APIResult<Vec<Issue>> {
+        let tag = self.api.get_tag_info(author.clone())?;
+        if tag.tag_type != TagType::Author {
+            let msg = format!("Tag {author} is not an author tag");
+            return Err(APIError::GenericError(msg));
+        }
+        self.search(Query::Author(author), loading)
+    }
+
+    /// Add the given tags to the given issues, in chunks of `chunk_size` issues.
+    // Issues are hashed by their ID only, which never changes.
+    #[allow(clippy::mutable_key_type)]
+    pub fn bulk_add_tags(&self,
+                         tags: HashMap<&Issue, Vec<String>>,
+                         chunk_size: usize) -> BulkTagReport {
+        let diffs = tags.into_iter()
+            .map(|(issue, add)| (issue.ident().clone(), TagDiff{add, remove: Vec::new()}))
+            .collect();
+        apply_tag_diffs(&self.api, diffs, chunk_size)
     }
 
-    pub fn bulk_add_tags(&self, tags: HashMap<&Issue, Vec<String>>) -> APIResult<()> {
-        let payload = tags.into_iter()
-            .map(|(k, v)| (k.ident().clone(), v))
+    /// Remove the given tags from the given issues, in chunks of `chunk_size` issues.
+    // Issues are hashed by their ID only, which never changes.
+    #[allow(clippy::mutable_key_type)]
+    pub fn bulk_remove_tags(&self,
+                            tags: HashMap<&Issue, Vec<String>>,
+                            chunk_size: usize) -> BulkTagReport {
+        let diffs = tags.into_iter()
+            .map(|(issue, remove)| (issue.ident().clone(), TagDiff{add: Vec::new(), remove}))
             .collect();
-        self.api.bulk_add_tags(payload)
+        apply_tag_diffs(&self.api, diffs, chunk_size)
+    }
+
+    /// Add and remove tags for many issues at once, in chunks of `chunk_size` issues.
+    // Issues are hashed by their ID only, which never changes.
+    #[allow(clippy::mutable_key_type)]
+    pub fn bulk_apply_tag_diff(&self,
+                               diffs: HashMap<&Issue, TagDiff>,
+                               chunk_size: usize) -> BulkTagReport {
+        let diffs = diffs.into_iter()
+            .map(|(issue, diff)| (issue.ident().clone(), diff))
+            .collect();
+        apply_tag_diffs(&self.api, diffs, chunk_size)
+    }
+
+    /// Like `bulk_apply_tag_diff`, but with issues identified by their ID.
+    pub(crate) fn bulk_apply_tag_diff_by_id(&self,
+                                            diffs: HashMap<String, TagDiff>,
+                                            chunk_size: usize) -> BulkTagReport {
+        apply_tag_diffs(&self.api, diffs.into_iter().collect(), chunk_size)
     }
 
     pub fn embeddings(&self) -> APIResult<Vec<Embedding>> {
@@ -205,7 +518,50 @@ impl IssueRepository {
         Ok(model)
     }
 
+    /// Look up a model version without loading the model first.
+    pub fn get_model_version(&self, model_id: String, version_id: String) -> APIResult<ModelVersion> {
+        let version = self.api.get_model_version(model_id, version_id)?;
+        Ok(version.into_bound_model(self.api.clone()))
+    }
+
+    /// Look up a test run without loading the model first.
+    pub fn get_test_run(&self, model_id: String, run_id: String) -> APIResult<TestRun> {
+        let run = self.api.get_test_run(model_id, run_id)?;
+        Ok(run.into_bound_test_run(self.api.clone()))
+    }
+
+    /// Versions of all models whose provenance matches the given predicate.
+    /// The server cannot search provenance, so the versions of every model
+    /// are listed. Models whose versions cannot be listed are reported
+    /// in the result, rather than failing the whole search.
+    pub fn versions_with_provenance<F>(&self, predicate: F) -> APIResult<ProvenanceMatches>
+    where
+        F: Fn(&Provenance) -> bool
+    {
+        let mut matches = ProvenanceMatches::default();
+        for model in self.models()? {
+            match model.versions_with_provenance(&predicate) {
+                Ok(versions) => matches.versions.extend(versions),
+                Err(e) => {
+                    tracing::warn!(model = model.identifier(), error = %e,
+                                   "failed to list versions while searching provenance");
+                    matches.failed.push((model.identifier(), e));
+                }
+            }
+        }
+        Ok(matches)
+    }
+
+    pub fn versions_using_embedding(&self, embedding_id: &str) -> APIResult<ProvenanceMatches> {
+        self.versions_with_provenance(|p| p.uses_embedding(embedding_id))
+    }
+
+    pub fn versions_using_file(&self, file_id: &str) -> APIResult<ProvenanceMatches> {
+        self.versions_with_provenance(|p| p.uses_file(file_id))
+    }
+
     pub fn add_model(&self, name: String, config: HashMap<String, Value>) -> APIResult<Model> {
+        self.api.model_config_schema().validate(&config)?;
         let id = self.api.create_model_config(name.clone(), config.clone())?;
         let m = Model::new(
             self.api.clone(),
@@ -217,6 +573,16 @@ impl IssueRepository {
         Ok(m)
     }
 
+    /// Schema which the configs of new and updated models must conform to.
+    /// Passing `None` disables validation.
+    pub fn set_model_config_schema(&self, schema: Option<ConfigSchema>) -> APIResult<()> {
+        self.api.model_config_schema().set(schema)
+    }
+
+    pub fn model_config_schema(&self) -> APIResult<Option<Value>> {
+        Ok(self.api.model_config_schema().get()?.map(|s| s.schema().clone()))
+    }
+
     pub fn delete_model_config(&self, model: Model) -> APIResult<()> {
         self.api.delete_model_config(model.identifier())
     }
@@ -244,6 +610,39 @@ impl IssueRepository {
     pub fn remove_file(&self, file: File) -> APIResult<()> {
         file.delete()
     }
+
+    /// Register a new label schema, or replace an existing one.
+    /// The built-in architectural schema cannot be replaced.
+    pub fn register_label_schema(&self, schema: LabelSchema) -> APIResult<()> {
+        self.api.label_schemas().register(schema)
+    }
+
+    pub fn label_schemas(&self) -> APIResult<Vec<LabelSchema>> {
+        self.api.label_schemas().all()
+    }
+
+    pub fn get_label_schema(&self, name: &str) -> APIResult<LabelSchema> {
+        self.api.label_schemas().get(name)
+    }
+
+    pub fn stats(&self) -> RequestStats {
+        self.api.metrics().snapshot()
+    }
+
+    pub fn reset_stats(&self) {
+        self.api.metrics().reset()
+    }
+
+    /// Limit the number of requests per second sent by this repository,
+    /// and every object obtained through it. Pass `None` to disable.
+    pub fn set_rate_limit(&self, requests_per_second: Option<f64>, burst: u32) -> APIResult<()> {
+        self.api.throttle().set_rate_limit(requests_per_second, burst)
+    }
+
+    /// Limit the number of concurrent requests. Pass `None` to disable.
+    pub fn set_max_in_flight(&self, limit: Option<usize>) -> APIResult<()> {
+        self.api.throttle().set_max_in_flight(limit)
+    }
 }
 
 
@@ -292,4 +691,76 @@ impl Repo {
         }
         Ok(projects)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use crate::query::QueryCMP;
+    use super::*;
+
+    /// Serve canned JSON responses, keyed by request path, on a local port.
+    /// Paths without a response are answered with the given fallback status.
+    fn serve(routes: Vec<(&'static str, &'static str)>, fallback_status: u16) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let path = request_line.split_whitespace().nth(1).unwrap_or("").to_string();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let (status, body) = match routes.iter().find(|(p, _)| *p == path) {
+                    Some((_, body)) => (200, *body),
+                    None => (fallback_status, "{}")
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} X\r\nContent-Type: application/json\r\n\
+                    Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                ).unwrap();
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn comment_search_falls_back_to_per_issue_requests() {
+        // Neither comment search nor bulk comment lookups are supported
+        let url = serve(vec![
+            ("/issue-ids", r#"{"issue_ids": ["i1", "i2"]}"#),
+            ("/manual-labels/i1/comments", r#"{"comments": {"c1": {"author": "alice", "comment": "needs work"}}}"#),
+            ("/manual-labels/i2/comments", r#"{"comments": {"c2": {"author": "bob", "comment": "fine"}}}"#),
+        ], 404);
+        let repo = IssueRepository{
+            label_caching: CachingPolicy::NoCaching,
+            config_handling: ConfigHandlingPolicy::ReadLocalWriteNoFetch,
+            prefetching: PrefetchPolicy::default(),
+            api: Arc::new(IssueAPI::new_read_only(url, false).unwrap())
+        };
+        let query = Query::Tag(QueryCMP::Eq, "has-label".to_string());
+        let filter = CommentFilter::new().author("alice".to_string());
+        let matches = repo.matching_labelling_comments(query, &filter).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].issue_id(), "i1");
+        assert_eq!(matches[0].identifier(), "c1");
+        assert_eq!(matches[0].text(), "needs work");
+    }
+}
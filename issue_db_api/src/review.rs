@@ -0,0 +1,114 @@
+use std::sync::Arc;
+use serde_json::{Map, Value};
+use crate::api_core::IssueAPI;
+use crate::comments::Comment;
+use crate::config::{CachingPolicy, IssueLoadingSettings, PrefetchPolicy};
+use crate::errors::APIResult;
+use crate::issues::Issue;
+
+/// Result of reviewing the label of an issue.
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReviewOutcome {
+    /// The existing label was correct.
+    Confirmed,
+    /// The label was replaced by the given label for the given schema.
+    Changed{schema: String, label: Map<String, Value>}
+}
+
+#[allow(unused)]
+impl ReviewOutcome {
+    /// Validate the outcome and convert it into the
+    /// payload of the `finish-review` endpoint.
+    pub(crate) fn into_payload(self, api: &IssueAPI) -> APIResult<Map<String, Value>> {
+        let mut map = Map::new();
+        match self {
+            ReviewOutcome::Confirmed => {
+                map.insert("outcome".to_string(), Value::String("confirmed".to_string()));
+            }
+            ReviewOutcome::Changed{schema, label} => {
+                let label = api.label_schemas().get(&schema)?.wrap(label)?;
+                map.insert("outcome".to_string(), Value::String("changed".to_string()));
+                map.insert("label".to_string(), Value::Object(label));
+            }
+        }
+        Ok(map)
+    }
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, serde::Deserialize)]
+pub(crate) struct UnboundReview {
+    pub(crate) issue_id: String,
+    #[serde(default)] pub(crate) reason: Option<String>,
+    #[serde(default)] pub(crate) requested_by: Option<String>,
+    #[serde(default)] pub(crate) reviewers: Vec<String>
+}
+
+impl UnboundReview {
+    pub(crate) fn into_bound_review(self,
+                                    api: Arc<IssueAPI>,
+                                    caching: CachingPolicy,
+                                    prefetching: PrefetchPolicy) -> Review {
+        Review{
+            api,
+            caching,
+            prefetching,
+            issue_id: self.issue_id,
+            reason: self.reason,
+            requested_by: self.requested_by,
+            reviewers: self.reviewers
+        }
+    }
+}
+
+/// An issue which is currently marked for review.
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct Review {
+    api: Arc<IssueAPI>,
+    caching: CachingPolicy,
+    prefetching: PrefetchPolicy,
+    issue_id: String,
+    reason: Option<String>,
+    requested_by: Option<String>,
+    reviewers: Vec<String>
+}
+
+#[allow(unused)]
+impl Review {
+    pub fn issue_id(&self) -> &String {
+        &self.issue_id
+    }
+
+    pub fn reason(&self) -> Option<&String> {
+        self.reason.as_ref()
+    }
+
+    pub fn requested_by(&self) -> Option<&String> {
+        self.requested_by.as_ref()
+    }
+
+    pub fn reviewers(&self) -> &Vec<String> {
+        &self.reviewers
+    }
+
+    pub fn issue(&self, loading: IssueLoadingSettings) -> APIResult<Issue> {
+        loading.with_default_prefetch_policy(self.prefetching)
+            .load_issue(self.api.clone(), self.issue_id.clone(), self.caching)
+    }
+
+    pub fn comments(&self) -> APIResult<Vec<Comment>> {
+        let comments = self.api
+            .get_labeling_comments_for_issue(self.issue_id.clone())?
+            .into_iter()
+            .map(|r| r.into_comment(self.api.clone(), self.issue_id.clone()))
+            .collect();
+        Ok(comments)
+    }
+
+    pub fn finish(&self, outcome: ReviewOutcome) -> APIResult<()> {
+        let payload = outcome.into_payload(&self.api)?;
+        self.api.finish_issue_review(self.issue_id.clone(), payload)
+    }
+}
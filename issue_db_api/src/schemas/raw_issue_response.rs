@@ -1,6 +1,6 @@
 use serde_json::Value;
 
-fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+pub(crate) fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
     where T: serde::Deserialize<'de>,
           D: serde::Deserializer<'de>
 {
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+use lazy_static::lazy_static;
+use crate::api_core::IssueAPI;
+use crate::config::{CachingPolicy, PrefetchPolicy};
+use crate::errors::APIResult;
+use crate::util::CacheLockError;
+
+/// A live connection to a server, together with the
+/// policies of the repository which created it.
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub(crate) struct Session {
+    pub(crate) api: Arc<IssueAPI>,
+    pub(crate) label_caching: CachingPolicy,
+    pub(crate) prefetching: PrefetchPolicy
+}
+
+#[derive(Debug)]
+struct SessionEntry {
+    api: Weak<IssueAPI>,
+    label_caching: CachingPolicy,
+    prefetching: PrefetchPolicy
+}
+
+lazy_static! {
+    /// Most recently created repository per server URL in this process.
+    /// Used to rebind detached (e.g. unpickled) objects to a repository.
+    static ref SESSIONS: Mutex<HashMap<String, SessionEntry>> = Mutex::new(HashMap::new());
+}
+
+pub(crate) fn register_session(api: &Arc<IssueAPI>,
+                               label_caching: CachingPolicy,
+                               prefetching: PrefetchPolicy) {
+    if let Ok(mut sessions) = SESSIONS.lock() {
+        let entry = SessionEntry{api: Arc::downgrade(api), label_caching, prefetching};
+        sessions.insert(api.url().clone(), entry);
+    }
+}
+
+/// Find a live repository connected to `url`.
+/// If there is none, a new read-only connection is made,
+/// which does not use label caching or prefetching.
+/// Writes through such a connection fail with `NotAuthorized`.
+pub(crate) fn find_or_connect(url: &String, allow_self_signed: bool) -> APIResult<Session> {
+    let mut sessions = SESSIONS.lock().map_err(|_| CacheLockError{})?;
+    if let Some(entry) = sessions.get(url) {
+        if let Some(api) = entry.api.upgrade() {
+            return Ok(
+                Session{api, label_caching: entry.label_caching, prefetching: entry.prefetching}
+            );
+        }
+    }
+    tracing::warn!(
+        url = url.as_str(),
+        "no repository connected to server; falling back to a read-only connection"
+    );
+    let api = Arc::new(IssueAPI::new_read_only(url.clone(), allow_self_signed)?);
+    let session = Session{
+        api: api.clone(),
+        label_caching: CachingPolicy::NoCaching,
+        prefetching: PrefetchPolicy::NoPrefetching
+    };
+    sessions.insert(
+        url.clone(),
+        SessionEntry{
+            api: Arc::downgrade(&api),
+            label_caching: session.label_caching,
+            prefetching: session.prefetching
+        }
+    );
+    Ok(session)
+}
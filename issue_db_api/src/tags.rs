@@ -1,14 +1,53 @@
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
 use crate::api_core::IssueAPI;
-use crate::errors::APIResult;
+use crate::config::{CachingPolicy, IssueLoadingSettings, PrefetchPolicy};
+use crate::errors::{APIError, APIResult};
+use crate::issues::Issue;
+use crate::query::{Query, QueryCMP};
+
+pub const DEFAULT_TAG_CHUNK_SIZE: usize = 1000;
 
 #[allow(unused)]
-#[derive(Debug, Clone, Copy, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum TagType {
-    #[serde(rename="author")] Author,
-    #[serde(rename="project")] Project,
-    #[serde(rename="manual-tag")] Custom
+    Author,
+    Project,
+    Custom,
+    /// Any tag type not (yet) known to this library.
+    Other(String)
+}
+
+#[allow(unused)]
+impl TagType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            TagType::Author => "author",
+            TagType::Project => "project",
+            TagType::Custom => "manual-tag",
+            TagType::Other(name) => name.as_str()
+        }
+    }
+}
+
+impl From<String> for TagType {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "author" => TagType::Author,
+            "project" => TagType::Project,
+            "manual-tag" => TagType::Custom,
+            _ => TagType::Other(value)
+        }
+    }
+}
+
+impl From<TagType> for String {
+    fn from(value: TagType) -> Self {
+        value.as_str().to_string()
+    }
 }
 
 #[allow(unused)]
@@ -20,9 +59,14 @@ pub(crate) struct UnboundTag {
 }
 
 impl UnboundTag {
-    pub(crate) fn into_bound_tag(self, api: Arc<IssueAPI>) -> Tag {
+    pub(crate) fn into_bound_tag(self,
+                                 api: Arc<IssueAPI>,
+                                 caching: CachingPolicy,
+                                 prefetching: PrefetchPolicy) -> Tag {
         Tag{
             api,
+            caching,
+            prefetching,
             name: self.name,
             description: self.description,
             tag_type: self.tag_type
@@ -34,6 +78,8 @@ impl UnboundTag {
 #[derive(Debug, Clone)]
 pub struct Tag {
     api: Arc<IssueAPI>,
+    caching: CachingPolicy,
+    prefetching: PrefetchPolicy,
     name: String,
     description: String,
     tag_type: TagType
@@ -69,7 +115,229 @@ impl Tag {
         Ok(())
     }
 
-    pub fn tag_type(&self) -> TagType {
-        self.tag_type
+    pub fn tag_type(&self) -> &TagType {
+        &self.tag_type
+    }
+
+    pub fn delete(&self) -> APIResult<()> {
+        self.api.delete_tag(self.name.clone())
+    }
+
+    fn issue_ids(&self) -> APIResult<Vec<String>> {
+        self.api.search(Query::Tag(QueryCMP::Eq, self.name.clone()))
+    }
+
+    /// All issues carrying this tag.
+    pub fn issues(&self, loading: IssueLoadingSettings) -> APIResult<Vec<Issue>> {
+        let ids = self.issue_ids()?;
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        loading.with_default_prefetch_policy(self.prefetching)
+            .load_issues(self.api.clone(), ids, self.caching)
+    }
+
+    /// Number of issues carrying this tag.
+    pub fn usage_count(&self) -> APIResult<usize> {
+        Ok(self.issue_ids()?.len())
+    }
+
+    /// Rename this tag. A new tag with the same description is created,
+    /// all issues carrying this tag are retagged, and this tag is deleted.
+    ///
+    /// This is not atomic: if retagging fails for some issues, both tags
+    /// are kept, this tag keeps its old name, and the failures are listed
+    /// in the returned report. Calling `rename` again resumes the migration;
+    /// the new tag is only created if it does not exist yet, and issues
+    /// which already carry it are not tagged again.
+    /// Renaming a tag to its current name does nothing.
+    pub fn rename(&mut self, new_name: String) -> APIResult<BulkTagReport> {
+        if new_name == self.name {
+            return Ok(BulkTagReport::default());
+        }
+        match self.api.get_tag_info(new_name.clone()) {
+            Ok(_) => {},
+            Err(APIError::NotFound(_)) => {
+                self.api.register_new_tag(
+                    new_name.clone(), self.description.clone(), Some(self.tag_type.clone())
+                )?;
+            },
+            Err(e) => return Err(e)
+        }
+        let report = self.retag(&new_name)?;
+        if report.is_success() {
+            self.name = new_name;
+        }
+        Ok(report)
+    }
+
+    /// Move all issues carrying this tag over to `target`,
+    /// and delete this tag.
+    ///
+    /// Like `rename`, this is not atomic: this tag is only deleted
+    /// if all issues were retagged, and calling `merge_into` again
+    /// retries the issues which failed.
+    /// A tag cannot be merged into itself.
+    pub fn merge_into(self, target: &Tag) -> APIResult<BulkTagReport> {
+        self.retag(&target.name)
+    }
+
+    /// Move all issues from this tag to `target`. Issues which carry both
+    /// tags (e.g. because an earlier attempt failed halfway) only have this
+    /// tag removed. This tag is deleted if all issues were retagged.
+    fn retag(&self, target: &str) -> APIResult<BulkTagReport> {
+        // Every issue would count as migrated, and lose this tag
+        if target == self.name {
+            let msg = format!("Cannot move tag {} to itself", self.name);
+            return Err(APIError::ValidationError(msg));
+        }
+        let migrated = self.api
+            .search(Query::And(vec![
+                Query::Tag(QueryCMP::Eq, self.name.clone()),
+                Query::Tag(QueryCMP::Eq, target.to_string())
+            ]))?
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let diffs = self.issue_ids()?
+            .into_iter()
+            .map(|id| {
+                let add = if migrated.contains(&id) { Vec::new() } else { vec![target.to_string()] };
+                (id, TagDiff{add, remove: vec![self.name.clone()]})
+            })
+            .collect();
+        let report = apply_tag_diffs(&self.api, diffs, DEFAULT_TAG_CHUNK_SIZE);
+        if report.is_success() {
+            self.api.delete_tag(self.name.clone())?;
+        } else {
+            tracing::warn!(
+                tag = self.name.as_str(), target, failed = report.failed.len(),
+                "retagging failed for some issues; keeping the tag"
+            );
+        }
+        Ok(report)
+    }
+}
+
+/// Tags to add to and remove from a single issue.
+#[allow(unused)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagDiff {
+    pub add: Vec<String>,
+    pub remove: Vec<String>
+}
+
+/// Outcome of a bulk tag operation, per issue.
+#[allow(unused)]
+#[derive(Debug, Default)]
+pub struct BulkTagReport {
+    pub succeeded: Vec<String>,
+    pub failed: Vec<(String, APIError)>
+}
+
+#[allow(unused)]
+impl BulkTagReport {
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Apply the given tag diffs in chunks of at most `chunk_size` issues,
+/// using one bulk request for additions and one for removals per chunk.
+/// If a bulk request fails, only that operation is retried issue by issue,
+/// so that failures can be attributed to individual issues.
+/// Tags are only removed from an issue once its additions succeeded,
+/// so that an issue which is being retagged never loses both tags.
+pub(crate) fn apply_tag_diffs(api: &IssueAPI,
+                              diffs: Vec<(String, TagDiff)>,
+                              chunk_size: usize) -> BulkTagReport {
+    let mut report = BulkTagReport::default();
+    for chunk in diffs.chunks(chunk_size.max(1)) {
+        let additions = chunk.iter()
+            .filter(|(_, d)| !d.add.is_empty())
+            .map(|(id, d)| (id.as_str(), &d.add))
+            .collect::<Vec<_>>();
+        let mut failed = apply_in_bulk(
+            additions, |p| api.bulk_add_tags(p), |id, tag| api.add_tag_to_issue(id, tag)
+        );
+        let removals = chunk.iter()
+            .filter(|(id, d)| !d.remove.is_empty() && !failed.contains_key(id))
+            .map(|(id, d)| (id.as_str(), &d.remove))
+            .collect::<Vec<_>>();
+        failed.extend(apply_in_bulk(
+            removals, |p| api.bulk_remove_tags(p), |id, tag| api.remove_tag_from_issue(id, tag)
+        ));
+        for (id, _) in chunk {
+            match failed.remove(id) {
+                None => report.succeeded.push(id.clone()),
+                Some(e) => report.failed.push((id.clone(), e))
+            }
+        }
+    }
+    report
+}
+
+/// Send a single bulk request for all given issues. If it fails,
+/// send one request per issue and tag instead. Returns the error
+/// for every issue which could not be updated.
+fn apply_in_bulk<B, S>(tags: Vec<(&str, &Vec<String>)>,
+                       bulk: B,
+                       single: S) -> HashMap<String, APIError>
+where
+    B: FnOnce(HashMap<String, Vec<String>>) -> APIResult<()>,
+    S: Fn(String, String) -> APIResult<()>
+{
+    let mut failed = HashMap::new();
+    if tags.is_empty() {
+        return failed;
+    }
+    let payload = tags.iter()
+        .map(|(id, t)| (id.to_string(), (*t).clone()))
+        .collect();
+    let Err(e) = bulk(payload) else {
+        return failed;
+    };
+    tracing::warn!(error = %e, issues = tags.len(), "bulk tag request failed; retrying per issue");
+    for (id, issue_tags) in tags {
+        let result = issue_tags.iter()
+            .try_for_each(|tag| single(id.to_string(), tag.clone()));
+        if let Err(e) = result {
+            failed.insert(id.to_string(), e);
+        }
+    }
+    failed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Points nowhere; these tests must not send any requests.
+    fn offline_tag(name: &str) -> Tag {
+        let api = IssueAPI::new_read_only("http://127.0.0.1:9".to_string(), false)
+            .expect("Failed to create client");
+        Tag{
+            api: Arc::new(api),
+            caching: CachingPolicy::NoCaching,
+            prefetching: PrefetchPolicy::default(),
+            name: name.to_string(),
+            description: String::new(),
+            tag_type: TagType::Custom
+        }
+    }
+
+    #[test]
+    fn rename_to_same_name_does_nothing() {
+        let mut tag = offline_tag("tag");
+        let report = tag.rename("tag".to_string()).unwrap();
+        assert!(report.succeeded.is_empty());
+        assert!(report.failed.is_empty());
+        assert_eq!(tag.name(), "tag");
+    }
+
+    #[test]
+    fn merge_into_itself_is_rejected() {
+        let tag = offline_tag("tag");
+        let target = tag.clone();
+        assert!(matches!(tag.merge_into(&target), Err(APIError::ValidationError(_))));
     }
 }
@@ -0,0 +1,134 @@
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+use crate::errors::{APIError, APIResult};
+
+#[allow(unused)]
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant
+}
+
+impl TokenBucket {
+    fn new(rate: f64, burst: u32) -> Self {
+        let capacity = f64::from(burst.max(1));
+        Self{rate, capacity, tokens: capacity, last_refill: Instant::now()}
+    }
+
+    /// Take a token if one is available. Otherwise, return
+    /// how long the caller has to wait for the next token.
+    fn try_take(&mut self) -> Option<Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+        }
+    }
+}
+
+#[allow(unused)]
+#[derive(Debug)]
+struct InFlight {
+    limit: Option<usize>,
+    active: usize
+}
+
+/// Client-side rate limiter (token bucket) combined with a cap on
+/// the number of concurrently running requests.
+/// Both limits are disabled by default.
+/// The throttle is owned by an `IssueAPI`, and is therefore shared by
+/// every object holding the same `Arc<IssueAPI>`.
+#[allow(unused)]
+#[derive(Debug)]
+pub(crate) struct RequestThrottle {
+    bucket: Mutex<Option<TokenBucket>>,
+    in_flight: Mutex<InFlight>,
+    released: Condvar
+}
+
+/// Occupies one request slot until dropped.
+pub(crate) struct ThrottlePermit<'a> {
+    throttle: &'a RequestThrottle
+}
+
+impl Drop for ThrottlePermit<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.throttle.in_flight.lock() {
+            state.active -= 1;
+            self.throttle.released.notify_one();
+        }
+    }
+}
+
+fn poisoned<T>(_: T) -> APIError {
+    APIError::LibraryError("Poisoned lock in request throttle. Cannot recover.".to_string())
+}
+
+#[allow(unused)]
+impl RequestThrottle {
+    pub(crate) fn new() -> Self {
+        Self{
+            bucket: Mutex::new(None),
+            in_flight: Mutex::new(InFlight{limit: None, active: 0}),
+            released: Condvar::new()
+        }
+    }
+
+    /// Limit the request rate to `requests_per_second`, allowing bursts
+    /// of at most `burst` requests. `None` disables rate limiting.
+    pub(crate) fn set_rate_limit(&self, requests_per_second: Option<f64>, burst: u32) -> APIResult<()> {
+        let bucket = match requests_per_second {
+            None => None,
+            Some(rate) if rate > 0.0 && rate.is_finite() => Some(TokenBucket::new(rate, burst)),
+            Some(rate) => {
+                let msg = format!("Invalid rate limit: {rate} requests per second");
+                return Err(APIError::GenericError(msg));
+            }
+        };
+        *self.bucket.lock().map_err(poisoned)? = bucket;
+        Ok(())
+    }
+
+    /// Limit the number of requests in flight at any point in time.
+    /// `None` removes the limit.
+    pub(crate) fn set_max_in_flight(&self, limit: Option<usize>) -> APIResult<()> {
+        if limit == Some(0) {
+            return Err(APIError::GenericError("Maximum number of requests in flight must be positive".to_string()));
+        }
+        let mut state = self.in_flight.lock().map_err(poisoned)?;
+        state.limit = limit;
+        // Raising the limit may unblock waiting threads
+        self.released.notify_all();
+        Ok(())
+    }
+
+    /// Block until both a concurrency slot and a rate limit token
+    /// are available.
+    pub(crate) fn acquire(&self) -> APIResult<ThrottlePermit<'_>> {
+        let mut state = self.in_flight.lock().map_err(poisoned)?;
+        while state.limit.is_some_and(|limit| state.active >= limit) {
+            state = self.released.wait(state).map_err(poisoned)?;
+        }
+        state.active += 1;
+        drop(state);
+        let permit = ThrottlePermit{throttle: self};
+        loop {
+            let wait = match self.bucket.lock().map_err(poisoned)?.as_mut() {
+                None => None,
+                Some(bucket) => bucket.try_take()
+            };
+            match wait {
+                None => break,
+                Some(duration) => std::thread::sleep(duration)
+            }
+        }
+        Ok(permit)
+    }
+}
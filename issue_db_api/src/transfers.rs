@@ -0,0 +1,69 @@
+use std::io::Read;
+use std::sync::Arc;
+use sha2::{Digest, Sha256};
+use crate::errors::APIResult;
+
+/// Called with the number of bytes transferred so far,
+/// and the total number of bytes (if known).
+pub type ProgressCallback = Arc<dyn Fn(u64, Option<u64>) + Send + Sync>;
+
+/// Suffix of the file an unfinished download is written to,
+/// so that it can be resumed later.
+const PARTIAL_DOWNLOAD_SUFFIX: &str = ".part";
+
+/// Path of the partial file for a download of `resource` to `path`.
+/// The path includes a hash of the resource, so that a partial download
+/// of one resource is never resumed as a download of another.
+pub(crate) fn partial_download_path(path: &str, resource: &str) -> String {
+    let key = hex::encode(Sha256::digest(resource.as_bytes()));
+    format!("{path}.{}{PARTIAL_DOWNLOAD_SUFFIX}", &key[..16])
+}
+
+/// Hex encoded SHA-256 hash of the contents of a file.
+pub(crate) fn sha256_file(path: &str) -> APIResult<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// The parts of a `Content-Range` header (`bytes <start>-<end>/<total>`
+/// or `bytes */<total>`) needed to resume a download.
+/// Parts given as `*` are `None`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct ContentRange {
+    pub(crate) start: Option<u64>,
+    pub(crate) total: Option<u64>
+}
+
+pub(crate) fn content_range(headers: &reqwest::header::HeaderMap) -> Option<ContentRange> {
+    let value = headers.get(reqwest::header::CONTENT_RANGE)?.to_str().ok()?;
+    let (range, total) = value.strip_prefix("bytes ")?.trim().split_once('/')?;
+    let start = range.split_once('-').and_then(|(start, _)| start.parse().ok());
+    Some(ContentRange{start, total: total.parse().ok()})
+}
+
+/// Reader reporting the number of bytes read to a progress callback.
+pub(crate) struct ProgressReader<R> {
+    inner: R,
+    done: u64,
+    total: Option<u64>,
+    progress: Option<ProgressCallback>
+}
+
+impl<R: Read> ProgressReader<R> {
+    pub(crate) fn new(inner: R, total: Option<u64>, progress: Option<ProgressCallback>) -> Self {
+        Self{inner, done: 0, total, progress}
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.done += n as u64;
+        if let Some(ref progress) = self.progress {
+            progress(self.done, self.total);
+        }
+        Ok(n)
+    }
+}
@@ -1,25 +1,8 @@
 use std::fmt::Debug;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock};
 use crate::errors::{APIResult};
-
-
-macro_rules! initialize_lazy_field {
-    ($e:expr) => {
-        {
-            let value: Option<_> = $e;
-            let lazy = lazy_init::Lazy::new();
-            if let Some(x) = value {
-                let _ = lazy.get_or_create(|| x);
-            }
-            lazy
-        }
-    }
-}
-
-
-pub(crate) use initialize_lazy_field;
-
+use crate::metrics::MetricsCollector;
 
 
 #[allow(unused)]
@@ -42,16 +25,18 @@ impl std::error::Error for CacheLockError {
 #[derive(Debug)]
 pub struct CacheContainer<T: Clone> {
     value: RwLock<Option<T>>,
-    dirty: AtomicBool
+    dirty: AtomicBool,
+    metrics: Arc<MetricsCollector>
 }
 
 
 impl<T: Clone + Debug> CacheContainer<T> {
-    pub fn new(initial: Option<T>) -> Self {
+    pub fn new(initial: Option<T>, metrics: Arc<MetricsCollector>) -> Self {
         let dirty = initial.is_none();
         CacheContainer{
             value: RwLock::new(initial),
-            dirty: AtomicBool::new(dirty)
+            dirty: AtomicBool::new(dirty),
+            metrics
         }
     }
 
@@ -60,6 +45,7 @@ impl<T: Clone + Debug> CacheContainer<T> {
             .read()
             .map_err(|_| CacheLockError{})?;
         if obj.is_some() && !self.dirty.load(Ordering::Acquire) {
+            self.metrics.record_cache_hit();
             Ok(obj.clone().unwrap())
         } else {
             drop(obj);    // unlock
@@ -70,6 +56,7 @@ impl<T: Clone + Debug> CacheContainer<T> {
             // With the write lock in hand, check if another thread
             // updated the object in the meantime.
             if self.dirty.load(Ordering::Relaxed) || obj.is_none() {
+                self.metrics.record_cache_miss();
                 let inner = (f)()?;
                 let _ = obj.insert(inner.clone());
                 self.dirty.store(false, Ordering::Release);
@@ -92,6 +79,19 @@ impl<T: Clone + Debug> CacheContainer<T> {
         Ok(())
     }
 
+    /// The cached value, if it is present and valid.
+    /// Never loads the value.
+    pub fn peek(&self) -> APIResult<Option<T>> {
+        let obj = self.value
+            .read()
+            .map_err(|_| CacheLockError{})?;
+        if self.dirty.load(Ordering::Acquire) {
+            Ok(None)
+        } else {
+            Ok(obj.clone())
+        }
+    }
+
     pub fn invalidate(&self) {
         self.dirty.store(true, Ordering::Release);
     }